@@ -1,5 +1,5 @@
-use crate::app_builder::AppBuilder;
-use bevy_ecs::{ParallelExecutor, Resources, Schedule, World};
+use crate::{app_builder::AppBuilder, event::Events};
+use bevy_ecs::{ParallelExecutor, Resources, Schedule, SystemPanic, World};
 #[cfg(feature = "trace")]
 use tracing::info_span;
 
@@ -33,6 +33,12 @@ pub struct App {
     pub executor: ParallelExecutor,
     pub startup_schedule: Schedule,
     pub startup_executor: ParallelExecutor,
+    pub shutdown_schedule: Schedule,
+    pub shutdown_executor: ParallelExecutor,
+    /// When set, [App::initialize] logs every stage that has no systems and every stage in a schedule's order that
+    /// doesn't exist
+    pub log_stage_diagnostics: bool,
+    initialized: bool,
 }
 
 impl Default for App {
@@ -44,7 +50,11 @@ impl Default for App {
             executor: Default::default(),
             startup_schedule: Default::default(),
             startup_executor: ParallelExecutor::without_tracker_clears(),
+            shutdown_schedule: Default::default(),
+            shutdown_executor: Default::default(),
+            log_stage_diagnostics: false,
             runner: Box::new(run_once),
+            initialized: false,
         }
     }
 }
@@ -52,6 +62,7 @@ impl Default for App {
 fn run_once(mut app: App) {
     app.initialize();
     app.update();
+    app.shutdown().exit_process_on_failure();
 }
 
 impl App {
@@ -64,6 +75,12 @@ impl App {
             .initialize(&mut self.world, &mut self.resources);
         self.executor
             .run(&mut self.schedule, &mut self.world, &mut self.resources);
+
+        // always drain, so panics caught without a SystemPanic event don't accumulate in the executor
+        let system_panics = self.executor.drain_system_panics();
+        if let Some(mut system_panic_events) = self.resources.get_mut::<Events<SystemPanic>>() {
+            system_panic_events.extend(system_panics);
+        }
     }
 
     pub fn initialize(&mut self) {
@@ -71,6 +88,11 @@ impl App {
         let startup_schedule_span = info_span!("startup_schedule");
         #[cfg(feature = "trace")]
         let _startup_schedule_guard = startup_schedule_span.enter();
+        if self.log_stage_diagnostics {
+            log_stage_diagnostics("startup schedule", &self.startup_schedule);
+            log_stage_diagnostics("schedule", &self.schedule);
+            log_stage_diagnostics("shutdown schedule", &self.shutdown_schedule);
+        }
         self.startup_schedule
             .initialize(&mut self.world, &mut self.resources);
         self.startup_executor.initialize(&mut self.resources);
@@ -79,6 +101,69 @@ impl App {
             &mut self.world,
             &mut self.resources,
         );
+        self.initialized = true;
+    }
+
+    /// Runs the shutdown schedule once. Runners call this after their loop exits (for example, because of an [AppExit]
+    /// event) so systems can save state and release resources before the app is dropped.
+    ///
+    /// This consumes any pending [AppExit] events and inserts a [ShutdownReason] resource for the shutdown systems. If
+    /// several exits are pending, the first one with a failure code decides the reason. Returns that exit (or a success
+    /// if none was pending), so runners can pass its code on with [AppExit::exit_process_on_failure].
+    pub fn shutdown(&mut self) -> AppExit {
+        #[cfg(feature = "trace")]
+        let shutdown_schedule_span = info_span!("shutdown_schedule");
+        #[cfg(feature = "trace")]
+        let _shutdown_schedule_guard = shutdown_schedule_span.enter();
+        // a failure wins over any success sent alongside it (for example, by a runner that reached its iteration limit)
+        let mut app_exit: Option<AppExit> = None;
+        if let Some(mut app_exit_events) = self.resources.get_mut::<Events<AppExit>>() {
+            for exit in app_exit_events.drain() {
+                if app_exit.map_or(true, |app_exit| app_exit.is_success()) {
+                    app_exit = Some(exit);
+                }
+            }
+        }
+        let shutdown_reason = if self.executor.has_panicked_systems() {
+            ShutdownReason::Panic
+        } else {
+            match app_exit {
+                Some(app_exit) if !app_exit.is_success() => ShutdownReason::Forced {
+                    code: app_exit.code,
+                },
+                _ => ShutdownReason::Normal,
+            }
+        };
+        self.resources.insert(shutdown_reason);
+
+        self.shutdown_schedule
+            .initialize(&mut self.world, &mut self.resources);
+        self.shutdown_executor.initialize(&mut self.resources);
+        self.shutdown_executor.run(
+            &mut self.shutdown_schedule,
+            &mut self.world,
+            &mut self.resources,
+        );
+
+        app_exit.unwrap_or_default()
+    }
+
+    /// Despawns every entity and resets the world's change trackers, but keeps all [Resources]. Call this between updates,
+    /// or send a [ResetWorld] event (see [AppBuilder::reset_world_on_event]) to do it from a system.
+    pub fn reset_world(&mut self) {
+        self.world.clear();
+        self.world.clear_trackers();
+    }
+
+    /// Runs the startup schedule (only the first time this is called) and then a single update of the schedule, all on
+    /// the calling thread. This ignores the app's runner, which makes it useful for tests that want to inspect the
+    /// [World] and [Resources] after a frame.
+    pub fn run_once(&mut self) {
+        if !self.initialized {
+            self.executor.initialize(&mut self.resources);
+            self.initialize();
+        }
+        self.update();
     }
 
     pub fn run(mut self) {
@@ -94,6 +179,63 @@ impl App {
     }
 }
 
+fn log_stage_diagnostics(schedule_name: &str, schedule: &Schedule) {
+    for stage_name in schedule.empty_stages() {
+        log::warn!("{} stage {} has no systems", schedule_name, stage_name);
+    }
+    for stage_name in schedule.missing_stages() {
+        log::error!(
+            "{} stage order contains {}, but the stage does not exist",
+            schedule_name,
+            stage_name
+        );
+    }
+}
+
+/// Why the [App] is shutting down. Inserted by [App::shutdown] before the shutdown schedule runs, so shutdown systems can
+/// decide how thorough their cleanup should be.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownReason {
+    /// The app finished or a successful [AppExit] was sent
+    Normal,
+    /// An [AppExit] with a failure code was sent
+    Forced { code: i32 },
+    /// A system panicked (with [AppBuilder::catch_system_panics] enabled) and was never resumed
+    Panic,
+}
+
+/// An event that makes the [App] despawn every entity at the end of the frame, for example when returning to a main menu.
+/// Only handled when the app was built with [AppBuilder::reset_world_on_event]. See [App::reset_world].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResetWorld;
+
 /// An event that indicates the app should exit. This will fully exit the app process.
-#[derive(Debug, Clone)]
-pub struct AppExit;
+///
+/// A non-zero `code` signals that the app failed. Runners exit the process with that code, which lets headless runs
+/// (for example in CI) report failures.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AppExit {
+    pub code: i32,
+}
+
+impl AppExit {
+    pub fn success() -> Self {
+        AppExit { code: 0 }
+    }
+
+    pub fn with_code(code: i32) -> Self {
+        AppExit { code }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.code == 0
+    }
+
+    /// Terminates the process with this exit's code if it signals a failure. Runners call this once they stop looping.
+    pub fn exit_process_on_failure(&self) {
+        if !self.is_success() {
+            log::error!("app exited with code {}", self.code);
+            std::process::exit(self.code);
+        }
+    }
+}
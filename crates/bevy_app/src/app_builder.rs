@@ -1,10 +1,14 @@
 use crate::{
-    app::{App, AppExit},
-    event::Events,
+    app::{App, AppExit, ResetWorld},
+    event::{EventChannel, EventReader, EventRegistry, Events},
     plugin::Plugin,
-    stage, startup_stage, PluginGroup, PluginGroupBuilder,
+    schedule_runner::{PostUpdateHook, RunMode, ScheduleRunnerSettings},
+    shutdown_stage, stage, startup_stage, PluginGroup, PluginGroupBuilder,
+};
+use bevy_ecs::{
+    Bundle, FromResources, IntoSystem, IntoThreadLocalSystem, ParallelExecutor, Resources, System,
+    SystemId, SystemPanic, SystemSet, World,
 };
-use bevy_ecs::{FromResources, IntoSystem, Resources, System, World};
 
 /// Configure [App]s using the builder pattern
 pub struct AppBuilder {
@@ -43,16 +47,50 @@ impl AppBuilder {
         app.run();
     }
 
+    /// Logs empty and missing stages when the app starts. See [App::log_stage_diagnostics].
+    pub fn log_stage_diagnostics(&mut self) -> &mut Self {
+        self.app.log_stage_diagnostics = true;
+        self
+    }
+
+    /// Logs a debug message as each stage of the [App]'s schedules starts and finishes. See
+    /// [Schedule::set_log_stages](bevy_ecs::Schedule::set_log_stages).
+    pub fn log_stages(&mut self) -> &mut Self {
+        self.app.startup_schedule.set_log_stages(true);
+        self.app.schedule.set_log_stages(true);
+        self.app.shutdown_schedule.set_log_stages(true);
+        self
+    }
+
     pub fn set_world(&mut self, world: World) -> &mut Self {
         self.app.world = world;
         self
     }
 
+    /// Replaces the executor that runs the [App]'s schedule
+    pub fn set_executor(&mut self, executor: ParallelExecutor) -> &mut Self {
+        self.app.executor = executor;
+        self
+    }
+
+    /// Replaces the executor that runs the [App]'s startup schedule. The default startup executor is created with
+    /// [ParallelExecutor::without_tracker_clears], so that changes made during startup are still visible to the first
+    /// update.
+    pub fn set_startup_executor(&mut self, executor: ParallelExecutor) -> &mut Self {
+        self.app.startup_executor = executor;
+        self
+    }
+
     pub fn add_stage(&mut self, stage_name: &'static str) -> &mut Self {
         self.app.schedule.add_stage(stage_name);
         self
     }
 
+    pub fn add_stage_at(&mut self, index: usize, stage_name: &'static str) -> &mut Self {
+        self.app.schedule.add_stage_at(index, stage_name);
+        self
+    }
+
     pub fn add_stage_after(&mut self, target: &'static str, stage_name: &'static str) -> &mut Self {
         self.app.schedule.add_stage_after(target, stage_name);
         self
@@ -118,6 +156,24 @@ impl AppBuilder {
         self.add_system_to_stage(stage, system)
     }
 
+    /// Runs `build` once and adds every system it returns to the [stage::UPDATE] stage. This lets several systems share
+    /// expensive setup work.
+    pub fn init_systems(
+        &mut self,
+        build: impl FnOnce(&mut Resources) -> Vec<Box<dyn System>>,
+    ) -> &mut Self {
+        self.init_systems_to_stage(stage::UPDATE, build)
+    }
+
+    pub fn init_systems_to_stage(
+        &mut self,
+        stage: &'static str,
+        build: impl FnOnce(&mut Resources) -> Vec<Box<dyn System>>,
+    ) -> &mut Self {
+        let systems = build(&mut self.app.resources);
+        self.add_systems_to_stage(stage, systems)
+    }
+
     pub fn add_startup_system_to_stage(
         &mut self,
         stage_name: &'static str,
@@ -169,10 +225,60 @@ impl AppBuilder {
         self.add_startup_system_to_stage(stage, system)
     }
 
+    /// Runs `build` once and adds every system it returns to the [startup_stage::STARTUP] stage
+    pub fn init_startup_systems(
+        &mut self,
+        build: impl FnOnce(&mut Resources) -> Vec<Box<dyn System>>,
+    ) -> &mut Self {
+        let systems = build(&mut self.app.resources);
+        self.add_startup_systems(systems)
+    }
+
+    pub fn add_shutdown_stage(&mut self, stage_name: &'static str) -> &mut Self {
+        self.app.shutdown_schedule.add_stage(stage_name);
+        self
+    }
+
+    /// Adds a system that runs once after the app's main loop exits. See [App::shutdown].
+    pub fn add_shutdown_system(&mut self, system: Box<dyn System>) -> &mut Self {
+        self.add_shutdown_system_to_stage(shutdown_stage::SHUTDOWN, system)
+    }
+
+    pub fn add_shutdown_system_to_stage(
+        &mut self,
+        stage_name: &'static str,
+        system: Box<dyn System>,
+    ) -> &mut Self {
+        self.app
+            .shutdown_schedule
+            .add_system_to_stage(stage_name, system);
+        self
+    }
+
+    /// Spawns an entity for each bundle in `bundles` during the [startup_stage::PRE_STARTUP] stage, so the entities
+    /// exist before any [startup_stage::STARTUP] system runs.
+    pub fn spawn_batch<I>(&mut self, bundles: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: Bundle + Send + Sync + 'static,
+    {
+        let mut bundles = Some(bundles.into_iter().collect::<Vec<_>>());
+        self.add_startup_system_to_stage(
+            startup_stage::PRE_STARTUP,
+            (move |world: &mut World, _resources: &mut Resources| {
+                if let Some(bundles) = bundles.take() {
+                    world.spawn_batch(bundles);
+                }
+            })
+            .thread_local_system(),
+        )
+    }
+
     pub fn add_default_stages(&mut self) -> &mut Self {
         self.add_startup_stage(startup_stage::PRE_STARTUP)
             .add_startup_stage(startup_stage::STARTUP)
             .add_startup_stage(startup_stage::POST_STARTUP)
+            .add_shutdown_stage(shutdown_stage::SHUTDOWN)
             .add_stage(stage::FIRST)
             .add_stage(stage::PRE_EVENT)
             .add_stage(stage::EVENT)
@@ -191,6 +297,46 @@ impl AppBuilder {
         self
     }
 
+    /// Adds a system to the [stage::UPDATE] stage and returns its [SystemId], which can later be used to remove it
+    pub fn add_system_with_id(&mut self, system: Box<dyn System>) -> SystemId {
+        self.add_system_to_stage_with_id(stage::UPDATE, system)
+    }
+
+    /// Adds a system to the given stage and returns its [SystemId], which can later be used to remove it
+    pub fn add_system_to_stage_with_id(
+        &mut self,
+        stage_name: &'static str,
+        system: Box<dyn System>,
+    ) -> SystemId {
+        let system_id = system.id();
+        self.app.schedule.add_system_to_stage(stage_name, system);
+        system_id
+    }
+
+    /// Adds a system built by `build` to each of the given stages. `build` is called once per stage, so each stage gets
+    /// its own system instance (with its own [SystemId] and local state).
+    pub fn add_system_to_stages(
+        &mut self,
+        stage_names: &[&'static str],
+        build: impl Fn() -> Box<dyn System>,
+    ) -> &mut Self {
+        for stage_name in stage_names {
+            self.app.schedule.add_system_to_stage(*stage_name, build());
+        }
+        self
+    }
+
+    /// Adds a system built by `build` to the given stage and another one to [startup_stage::STARTUP], so the same logic
+    /// also runs once before the first update. Each instance has its own [SystemId] and local state.
+    pub fn add_system_and_startup(
+        &mut self,
+        stage_name: &'static str,
+        build: impl Fn() -> Box<dyn System>,
+    ) -> &mut Self {
+        self.add_startup_system(build())
+            .add_system_to_stage(stage_name, build())
+    }
+
     pub fn add_system_to_stage_front(
         &mut self,
         stage_name: &'static str,
@@ -213,12 +359,110 @@ impl AppBuilder {
         self
     }
 
+    pub fn add_system_set(&mut self, system_set: SystemSet) -> &mut Self {
+        self.add_system_set_to_stage(stage::UPDATE, system_set)
+    }
+
+    pub fn add_system_set_to_stage(
+        &mut self,
+        stage_name: &'static str,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        self.app
+            .schedule
+            .add_system_set_to_stage(stage_name, system_set);
+        self
+    }
+
+    /// Removes the system with the given id from the [App]'s schedule
+    pub fn remove_system(&mut self, system_id: SystemId) -> &mut Self {
+        self.app.schedule.remove_system(system_id);
+        self
+    }
+
+    /// Removes every system in the system set with the given label from the [App]'s schedule
+    pub fn remove_system_set(&mut self, label: &str) -> &mut Self {
+        self.app.schedule.remove_system_set(label);
+        self
+    }
+
     pub fn add_event<T>(&mut self) -> &mut Self
     where
         T: Send + Sync + 'static,
     {
+        self.add_event_to_stage::<T>(stage::EVENT)
+    }
+
+    /// Like [AppBuilder::add_event], but advances the event buffers in the given stage instead of [stage::EVENT]
+    pub fn add_event_to_stage<T>(&mut self, stage_name: &'static str) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        if self.app.schedule.stage(stage_name).is_none() {
+            panic!(
+                "Stage does not exist: {} (while adding event {})",
+                stage_name,
+                std::any::type_name::<T>()
+            );
+        }
+
+        self.resources_mut()
+            .get_or_insert_with(EventRegistry::default)
+            .register::<T>();
         self.add_resource(Events::<T>::default())
-            .add_system_to_stage(stage::EVENT, Events::<T>::update_system.system())
+            .add_system_to_stage(stage_name, Events::<T>::update_system.system())
+    }
+
+    /// Catches panics in the [App]'s systems instead of unwinding through the schedule. A system that panics is skipped
+    /// from then on and a [SystemPanic] event is sent, so the app can keep running or send [AppExit] itself.
+    pub fn catch_system_panics(&mut self) -> &mut Self {
+        self.app.executor.set_catch_system_panics(true);
+        if !self.app.resources.contains::<Events<SystemPanic>>() {
+            self.add_event::<SystemPanic>();
+        }
+        self
+    }
+
+    /// Adds an [EventChannel] resource for `T`, which lets code outside of the app's systems send `T` events. The events
+    /// are sent during [stage::FIRST]. Adds the event with [AppBuilder::add_event] if it hasn't been added yet.
+    pub fn add_event_channel<T>(&mut self) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        if !self.app.resources.contains::<Events<T>>() {
+            self.add_event::<T>();
+        }
+
+        self.init_resource::<EventChannel<T>>()
+            .add_system_to_stage(stage::FIRST, EventChannel::<T>::drain_system.system())
+    }
+
+    /// Despawns every entity at the end of any frame in which a [ResetWorld] event was sent. [Resources] are kept.
+    pub fn reset_world_on_event(&mut self) -> &mut Self {
+        let mut reset_world_reader = EventReader::<ResetWorld>::default();
+        self.add_event::<ResetWorld>().add_system_to_stage(
+            stage::LAST,
+            (move |world: &mut World, resources: &mut Resources| {
+                let reset = resources
+                    .get::<Events<ResetWorld>>()
+                    .map_or(false, |events| reset_world_reader.latest(&events).is_some());
+                if reset {
+                    world.clear();
+                    world.clear_trackers();
+                }
+            })
+            .thread_local_system(),
+        )
+    }
+
+    /// Adds an extra [Events::update_system] for `T` to the given stage, on top of the one [AppBuilder::add_event] adds
+    /// to [stage::EVENT]. Every update drops the events that were sent before the previous update, so updating
+    /// mid-frame lets events expire sooner than the usual two frames.
+    pub fn add_event_update_to_stage<T>(&mut self, stage_name: &'static str) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+    {
+        self.add_system_to_stage(stage_name, Events::<T>::update_system.system())
     }
 
     /// Adds a resource to the current [App] and overwrites any resource previously added of the same type.
@@ -230,6 +474,22 @@ impl AppBuilder {
         self
     }
 
+    /// Removes a resource previously added to the current [App] and returns it, if it exists.
+    pub fn remove_resource<T>(&mut self) -> Option<T>
+    where
+        T: Send + Sync + 'static,
+    {
+        self.app.resources.remove::<T>()
+    }
+
+    /// Returns true if the current [App] has a resource of type `T`.
+    pub fn has_resource<T>(&self) -> bool
+    where
+        T: Send + Sync + 'static,
+    {
+        self.app.resources.contains::<T>()
+    }
+
     pub fn add_thread_local_resource<T>(&mut self, resource: T) -> &mut Self
     where
         T: 'static,
@@ -248,6 +508,18 @@ impl AppBuilder {
         self
     }
 
+    /// Inserts the resource returned by `build`, which can read any resources added so far. Unlike [AppBuilder::init_resource]
+    /// this does not require `R` to implement [FromResources].
+    pub fn init_resource_with<R>(&mut self, build: impl FnOnce(&Resources) -> R) -> &mut Self
+    where
+        R: Send + Sync + 'static,
+    {
+        let resource = build(&self.app.resources);
+        self.app.resources.insert(resource);
+
+        self
+    }
+
     pub fn init_thread_local_resource<R>(&mut self) -> &mut Self
     where
         R: FromResources + 'static,
@@ -258,6 +530,25 @@ impl AppBuilder {
         self
     }
 
+    /// Sets the [RunMode] used by the [ScheduleRunnerPlugin](crate::ScheduleRunnerPlugin), replacing any previously
+    /// configured mode
+    pub fn set_run_mode(&mut self, run_mode: RunMode) -> &mut Self {
+        self.app
+            .resources
+            .get_or_insert_with(ScheduleRunnerSettings::default)
+            .run_mode = run_mode;
+        self
+    }
+
+    /// Sets the [PostUpdateHook] the [ScheduleRunnerPlugin](crate::ScheduleRunnerPlugin) calls after every update,
+    /// replacing any previously set hook
+    pub fn set_post_update_hook(
+        &mut self,
+        hook: impl FnMut(&mut World, &mut Resources) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.add_resource(PostUpdateHook::new(hook))
+    }
+
     pub fn set_runner(&mut self, run_fn: impl Fn(App) + 'static) -> &mut Self {
         self.app.runner = Box::new(run_fn);
         self
@@ -1,5 +1,9 @@
-use bevy_ecs::ResMut;
-use std::marker::PhantomData;
+use bevy_ecs::{Res, ResMut, Resource, Resources, SystemParam, World};
+use std::{
+    any::TypeId,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
 
 #[derive(Debug)]
 struct EventInstance<T> {
@@ -59,8 +63,6 @@ enum State {
 pub struct Events<T> {
     events_a: Vec<EventInstance<T>>,
     events_b: Vec<EventInstance<T>>,
-    a_start_event_count: usize,
-    b_start_event_count: usize,
     event_count: usize,
     state: State,
 }
@@ -68,8 +70,6 @@ pub struct Events<T> {
 impl<T> Default for Events<T> {
     fn default() -> Self {
         Events {
-            a_start_event_count: 0,
-            b_start_event_count: 0,
             event_count: 0,
             events_a: Vec::new(),
             events_b: Vec::new(),
@@ -82,6 +82,13 @@ fn map_instance_event<T>(event_instance: &EventInstance<T>) -> &T {
     &event_instance.event
 }
 
+/// Returns the index of the first event in `events` that a reader that has seen `last_event_count` events hasn't read yet
+fn first_unread_index<T>(events: &[EventInstance<T>], last_event_count: usize) -> usize {
+    match events.binary_search_by_key(&last_event_count, |instance| instance.event_count) {
+        Ok(index) | Err(index) => index,
+    }
+}
+
 /// Reads events of type `T` in order and tracks which events have already been read.
 pub struct EventReader<T> {
     last_event_count: usize,
@@ -101,18 +108,10 @@ impl<T> EventReader<T> {
     /// Iterates over the events this EventReader has not seen yet. This updates the EventReader's
     /// event counter, which means subsequent event reads will not include events that happened before now.
     pub fn iter<'a>(&mut self, events: &'a Events<T>) -> impl DoubleEndedIterator<Item = &'a T> {
-        // if the reader has seen some of the events in a buffer, find the proper index offset.
+        // if the reader has seen some of the events in a buffer, skip them.
         // otherwise read all events in the buffer
-        let a_index = if self.last_event_count > events.a_start_event_count {
-            self.last_event_count - events.a_start_event_count
-        } else {
-            0
-        };
-        let b_index = if self.last_event_count > events.b_start_event_count {
-            self.last_event_count - events.b_start_event_count
-        } else {
-            0
-        };
+        let a_index = first_unread_index(&events.events_a, self.last_event_count);
+        let b_index = first_unread_index(&events.events_b, self.last_event_count);
         self.last_event_count = events.event_count;
         match events.state {
             State::A => events
@@ -167,6 +166,48 @@ impl<T> EventReader<T> {
     pub fn earliest<'a>(&mut self, events: &'a Events<T>) -> Option<&'a T> {
         self.iter(events).next()
     }
+
+    /// Removes the events this EventReader has not seen yet from `events` and returns them in order. Other readers will
+    /// not see the removed events. This updates the EventReader's event counter, which means subsequent event reads will
+    /// not include events that happened before now.
+    pub fn drain(&mut self, events: &mut Events<T>) -> Vec<T> {
+        let a_index = first_unread_index(&events.events_a, self.last_event_count);
+        let b_index = first_unread_index(&events.events_b, self.last_event_count);
+        self.last_event_count = events.event_count;
+        let map = |i: EventInstance<T>| i.event;
+        match events.state {
+            State::A => events
+                .events_b
+                .drain(b_index..)
+                .map(map)
+                .chain(events.events_a.drain(a_index..).map(map))
+                .collect(),
+            State::B => events
+                .events_a
+                .drain(a_index..)
+                .map(map)
+                .chain(events.events_b.drain(b_index..).map(map))
+                .collect(),
+        }
+    }
+}
+
+/// Sends events of type `T`. Systems can use this as a parameter instead of `ResMut<Events<T>>`.
+#[derive(SystemParam)]
+pub struct EventWriter<'a, T: Resource> {
+    events: ResMut<'a, Events<T>>,
+}
+
+impl<'a, T: Resource> EventWriter<'a, T> {
+    /// "Sends" an `event` by writing it to the current event buffer. [EventReader]s can then read the event.
+    pub fn send(&mut self, event: T) {
+        self.events.send(event);
+    }
+
+    /// Sends every event in `events`, in order
+    pub fn send_batch(&mut self, events: impl Iterator<Item = T>) {
+        self.events.extend(events);
+    }
 }
 
 impl<T: bevy_ecs::Resource> Events<T> {
@@ -207,12 +248,10 @@ impl<T: bevy_ecs::Resource> Events<T> {
             State::A => {
                 self.events_b = Vec::new();
                 self.state = State::B;
-                self.b_start_event_count = self.event_count;
             }
             State::B => {
                 self.events_a = Vec::new();
                 self.state = State::A;
-                self.a_start_event_count = self.event_count;
             }
         }
     }
@@ -222,6 +261,23 @@ impl<T: bevy_ecs::Resource> Events<T> {
         events.update();
     }
 
+    /// Returns the number of events that can still be read, including the ones sent before the last update
+    pub fn len(&self) -> usize {
+        self.events_a.len() + self.events_b.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of events sent since the last update
+    pub fn current_update_len(&self) -> usize {
+        match self.state {
+            State::A => self.events_a.len(),
+            State::B => self.events_b.len(),
+        }
+    }
+
     /// Removes all events.
     pub fn clear(&mut self) {
         self.events_a.clear();
@@ -267,6 +323,125 @@ impl<T: bevy_ecs::Resource> Events<T> {
     }
 }
 
+/// A thread safe handle for sending `T` events from outside of any system, for example from a host program that embeds
+/// the app. Events sent through the channel are queued and moved into [Events] at the start of the next frame, so they
+/// can't race with [Events::update]. Added with [AppBuilder::add_event_channel](crate::AppBuilder::add_event_channel);
+/// clones share the same queue, so a clone can be taken from [Resources] before the app runs.
+pub struct EventChannel<T> {
+    queue: Arc<Mutex<Vec<T>>>,
+}
+
+impl<T> Clone for EventChannel<T> {
+    fn clone(&self) -> Self {
+        EventChannel {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<T> Default for EventChannel<T> {
+    fn default() -> Self {
+        EventChannel {
+            queue: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+}
+
+impl<T: Resource> EventChannel<T> {
+    /// Queues `event` to be sent at the start of the next frame
+    pub fn send(&self, event: T) {
+        self.queue.lock().unwrap().push(event);
+    }
+
+    /// A system that sends every queued event to [Events]
+    pub fn drain_system(channel: Res<Self>, mut events: ResMut<Events<T>>) {
+        events.extend(channel.queue.lock().unwrap().drain(..));
+    }
+}
+
+/// The number of events of a registered type that are in its [Events] resource
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventCount {
+    pub name: &'static str,
+    /// Events sent since the last [Events::update]
+    pub current: usize,
+    /// Events that can still be read, including the ones sent before the last [Events::update]
+    pub buffered: usize,
+}
+
+struct RegisteredEvent {
+    type_id: TypeId,
+    name: &'static str,
+    count: fn(&Resources) -> Option<(usize, usize)>,
+}
+
+/// Records every event type added with [AppBuilder::add_event](crate::AppBuilder::add_event), so tooling can enumerate
+/// the [Events] in [Resources] without knowing their types.
+#[derive(Default)]
+pub struct EventRegistry {
+    events: Vec<RegisteredEvent>,
+}
+
+impl EventRegistry {
+    pub fn register<T: Resource>(&mut self) {
+        if self.contains::<T>() {
+            return;
+        }
+
+        self.events.push(RegisteredEvent {
+            type_id: TypeId::of::<T>(),
+            name: std::any::type_name::<T>(),
+            count: |resources| {
+                resources
+                    .get::<Events<T>>()
+                    .map(|events| (events.current_update_len(), events.len()))
+            },
+        });
+    }
+
+    pub fn contains<T: Resource>(&self) -> bool {
+        let type_id = TypeId::of::<T>();
+        self.events.iter().any(|event| event.type_id == type_id)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.events.iter().map(|event| event.name)
+    }
+
+    /// Returns the current event counts of every registered event type that has an [Events] resource
+    pub fn counts(&self, resources: &Resources) -> Vec<EventCount> {
+        self.events
+            .iter()
+            .filter_map(|event| {
+                (event.count)(resources).map(|(current, buffered)| EventCount {
+                    name: event.name,
+                    current,
+                    buffered,
+                })
+            })
+            .collect()
+    }
+
+    /// A thread local system that logs how many events of each registered type were sent since their last update
+    pub fn log_counts_system(_world: &mut World, resources: &mut Resources) {
+        let registry = match resources.get::<EventRegistry>() {
+            Some(registry) => registry,
+            None => return,
+        };
+
+        for count in registry.counts(resources) {
+            if count.current > 0 {
+                log::debug!(
+                    "{}: {} sent, {} buffered",
+                    count.name,
+                    count.current,
+                    count.buffered
+                );
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -366,6 +541,73 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_reader_drain() {
+        let mut events = Events::<TestEvent>::default();
+        let event_0 = TestEvent { i: 0 };
+        let event_1 = TestEvent { i: 1 };
+        let event_2 = TestEvent { i: 2 };
+
+        let mut reader_a = events.get_reader();
+        let mut reader_b = events.get_reader();
+
+        events.send(event_0);
+        assert_eq!(get_events(&events, &mut reader_b), vec![event_0]);
+
+        events.update();
+        events.send(event_1);
+
+        assert_eq!(
+            reader_a.drain(&mut events),
+            vec![event_0, event_1],
+            "reader_a drains all unread events across both buffers"
+        );
+        assert_eq!(
+            reader_a.drain(&mut events),
+            vec![],
+            "second drain of reader_a results in zero events"
+        );
+        assert_eq!(
+            get_events(&events, &mut reader_b),
+            vec![],
+            "reader_b does not receive events drained by reader_a"
+        );
+
+        events.send(event_2);
+
+        assert_eq!(
+            get_events(&events, &mut reader_b),
+            vec![event_2],
+            "reader_b receives events sent after the drain"
+        );
+        assert_eq!(get_events(&events, &mut reader_a), vec![event_2]);
+    }
+
+    #[test]
+    fn event_registry_counts() {
+        let mut resources = Resources::default();
+        let mut registry = EventRegistry::default();
+        registry.register::<TestEvent>();
+        registry.register::<TestEvent>();
+        assert_eq!(registry.names().count(), 1);
+        assert!(registry.counts(&resources).is_empty());
+
+        let mut events = Events::<TestEvent>::default();
+        events.send(TestEvent { i: 0 });
+        events.update();
+        events.send(TestEvent { i: 1 });
+        resources.insert(events);
+
+        assert_eq!(
+            registry.counts(&resources),
+            vec![EventCount {
+                name: std::any::type_name::<TestEvent>(),
+                current: 1,
+                buffered: 2,
+            }]
+        );
+    }
+
     fn get_events(
         events: &Events<TestEvent>,
         reader: &mut EventReader<TestEvent>,
@@ -1,3 +1,5 @@
+/// The names of the default App shutdown stages
+pub mod shutdown_stage;
 /// The names of the default App stages
 pub mod stage;
 /// The names of the default App startup stages
@@ -22,7 +24,7 @@ pub mod prelude {
     pub use crate::{
         app::App,
         app_builder::AppBuilder,
-        event::{EventReader, Events},
+        event::{EventReader, EventWriter, Events},
         stage, DynamicPlugin, Plugin, PluginGroup,
     };
 }
@@ -3,8 +3,13 @@ use crate::{
     app::AppExit,
     event::{EventReader, Events},
     plugin::Plugin,
+    stage,
+};
+use bevy_ecs::{Resources, World};
+use std::{
+    sync::{Arc, Condvar, Mutex},
+    time::Duration,
 };
-use std::time::Duration;
 
 #[cfg(target_arch = "wasm32")]
 use instant::Instant;
@@ -19,25 +24,214 @@ use wasm_bindgen::{prelude::*, JsCast};
 /// Determines the method used to run an [App]'s `Schedule`
 #[derive(Copy, Clone, Debug)]
 pub enum RunMode {
-    Loop { wait: Option<Duration> },
+    Loop {
+        wait: Option<Duration>,
+        /// Stops the loop (by sending [AppExit]) after this many updates. Runs forever when `None`.
+        max_iterations: Option<usize>,
+    },
     Once,
+    /// Runs one update, then blocks until [RunnerWaker::wake] is called (or `timeout` passes) before running the next
+    /// one. This avoids polling when work arrives sporadically, for example from the network. Not supported on wasm.
+    OnSignal {
+        timeout: Option<Duration>,
+    },
+}
+
+impl RunMode {
+    /// Loops forever, waiting between updates so that the schedule runs `fps` times per second
+    pub fn loop_at_fps(fps: f64) -> Self {
+        assert!(
+            fps > 0.0 && fps.is_finite(),
+            "fps must be positive and finite: {}",
+            fps
+        );
+        RunMode::Loop {
+            wait: Some(Duration::from_secs_f64(1.0 / fps)),
+            max_iterations: None,
+        }
+    }
 }
 
 impl Default for RunMode {
     fn default() -> Self {
-        RunMode::Loop { wait: None }
+        RunMode::Loop {
+            wait: None,
+            max_iterations: None,
+        }
+    }
+}
+
+/// Wakes a [ScheduleRunnerPlugin] running in [RunMode::OnSignal]. The plugin inserts this resource; clones share the same
+/// signal, so a clone can be handed to another thread (or kept by the host) before the app runs.
+#[derive(Clone, Default)]
+pub struct RunnerWaker {
+    signal: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl RunnerWaker {
+    /// Makes the runner run its next update. Wakes sent while an update is running are not lost: the runner starts
+    /// another update as soon as the current one finishes.
+    pub fn wake(&self) {
+        let (woken, condvar) = &*self.signal;
+        *woken.lock().unwrap() = true;
+        condvar.notify_one();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wait(&self, timeout: Option<Duration>) {
+        let (woken, condvar) = &*self.signal;
+        let mut woken = woken.lock().unwrap();
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        while !*woken {
+            match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        break;
+                    }
+                    woken = condvar.wait_timeout(woken, deadline - now).unwrap().0;
+                }
+                None => woken = condvar.wait(woken).unwrap(),
+            }
+        }
+        *woken = false;
+    }
+}
+
+/// Host code that the [ScheduleRunnerPlugin] calls after every update, outside of the schedule. Unlike a thread local
+/// system, it isn't part of any stage, so it can't be reordered or removed by plugins. Useful for pumping an external
+/// event loop between frames.
+///
+/// The runner takes this resource out of [Resources] when the app starts running, so it has to be inserted while
+/// building the app, for example with [AppBuilder::set_post_update_hook].
+pub struct PostUpdateHook(Box<dyn FnMut(&mut World, &mut Resources) + Send + Sync>);
+
+impl PostUpdateHook {
+    pub fn new(hook: impl FnMut(&mut World, &mut Resources) + Send + Sync + 'static) -> Self {
+        PostUpdateHook(Box::new(hook))
+    }
+
+    fn run(&mut self, app: &mut App) {
+        (self.0)(&mut app.world, &mut app.resources);
+    }
+}
+
+/// The number of updates the [ScheduleRunnerPlugin] loop has completed so far
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct LoopIterations(pub usize);
+
+/// Overrides the `wait` duration of [RunMode::Loop] while present. The [ScheduleRunnerPlugin] loop reads this resource
+/// after every update, so systems can change the loop's cadence while the app is running (for example, to tick less
+/// often while a server is idle). Removing the resource restores the configured `wait`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ScheduleWait(pub Duration);
+
+/// Determines how the [ScheduleRunnerPlugin] loop waits out the rest of a frame when [RunMode::Loop] has a `wait` duration.
+/// This is ignored on wasm, where the loop is driven by `setTimeout`.
+#[derive(Copy, Clone, Debug)]
+pub enum WaitStrategy {
+    /// Sleeps for the rest of the frame. This uses the least CPU, but the OS may oversleep by several milliseconds.
+    Sleep,
+    /// Sleeps for all but the last `spin` of the frame, then busy-waits until the frame is over. This trades CPU time for
+    /// more precise frame pacing.
+    SpinSleep { spin: Duration },
+    /// Repeatedly yields the thread to the OS until the frame is over.
+    Yield,
+    /// Sleeps for the rest of the frame minus the oversleep measured on earlier frames, then busy-waits until the frame is
+    /// over. This adapts to the OS's sleep precision, which keeps short frame periods (for example, at 240 fps) steady
+    /// without spinning for longer than needed. See [FramePacingStats] for the achieved precision.
+    Adaptive,
+}
+
+impl Default for WaitStrategy {
+    fn default() -> Self {
+        WaitStrategy::Sleep
+    }
+}
+
+/// How precisely the [ScheduleRunnerPlugin] loop has waited out the rest of each frame. Jitter is the difference between
+/// how long the loop meant to wait and how long it actually waited. Updated after every wait.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FramePacingStats {
+    pub last_jitter: Duration,
+    /// A moving average of recent jitter
+    pub average_jitter: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FramePacingStats {
+    fn record(&mut self, jitter: Duration) {
+        self.last_jitter = jitter;
+        self.average_jitter = (self.average_jitter * 7 + jitter) / 8;
+    }
+}
+
+/// Sleep overshoot state kept across frames by [WaitStrategy::Adaptive]
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct FramePacer {
+    estimated_oversleep: Duration,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WaitStrategy {
+    fn wait(&self, duration: Duration, pacer: &mut FramePacer) {
+        match *self {
+            WaitStrategy::Sleep => thread::sleep(duration),
+            WaitStrategy::SpinSleep { spin } => {
+                let end = Instant::now() + duration;
+                if duration > spin {
+                    thread::sleep(duration - spin);
+                }
+                while Instant::now() < end {
+                    std::hint::spin_loop();
+                }
+            }
+            WaitStrategy::Yield => {
+                let end = Instant::now() + duration;
+                while Instant::now() < end {
+                    thread::yield_now();
+                }
+            }
+            WaitStrategy::Adaptive => {
+                let end = Instant::now() + duration;
+                if duration > pacer.estimated_oversleep {
+                    let sleep = duration - pacer.estimated_oversleep;
+                    let sleep_start = Instant::now();
+                    thread::sleep(sleep);
+                    let oversleep = sleep_start.elapsed().checked_sub(sleep).unwrap_or_default();
+                    pacer.estimated_oversleep = (pacer.estimated_oversleep * 7 + oversleep) / 8;
+                }
+                while Instant::now() < end {
+                    std::hint::spin_loop();
+                }
+            }
+        }
     }
 }
 
 #[derive(Copy, Clone, Default)]
 pub struct ScheduleRunnerSettings {
     pub run_mode: RunMode,
+    pub wait_strategy: WaitStrategy,
+    /// Sends a [ScheduleOverrun] event whenever this many consecutive updates take longer than the loop's `wait`
+    /// duration. `0` disables overrun events.
+    pub overrun_frames: u32,
+}
+
+/// Sent by the [ScheduleRunnerPlugin] loop when updates have taken longer than the loop's `wait` duration for
+/// [ScheduleRunnerSettings::overrun_frames] consecutive frames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleOverrun {
+    /// How much longer than `wait` the most recent update took
+    pub over_by: Duration,
 }
 
 impl ScheduleRunnerSettings {
     pub fn run_once() -> Self {
         ScheduleRunnerSettings {
             run_mode: RunMode::Once,
+            ..Default::default()
         }
     }
 
@@ -45,30 +239,111 @@ impl ScheduleRunnerSettings {
         ScheduleRunnerSettings {
             run_mode: RunMode::Loop {
                 wait: Some(wait_duration),
+                max_iterations: None,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Loops forever, running the schedule `fps` times per second
+    pub fn run_at_fps(fps: f64) -> Self {
+        ScheduleRunnerSettings {
+            run_mode: RunMode::loop_at_fps(fps),
+            ..Default::default()
+        }
+    }
+
+    /// Runs the schedule exactly `iterations` times as fast as possible, then exits the app
+    pub fn run_for(iterations: usize) -> Self {
+        ScheduleRunnerSettings {
+            run_mode: RunMode::Loop {
+                wait: None,
+                max_iterations: Some(iterations),
             },
+            ..Default::default()
         }
     }
 }
 
 /// Configures an App to run its [Schedule](bevy_ecs::Schedule) according to a given [RunMode]
+///
+/// The [ScheduleRunnerSettings] resource is read when the app starts running (after the startup schedule), so it can be
+/// changed at any point while building the app, for example with [AppBuilder::set_run_mode].
 #[derive(Default)]
 pub struct ScheduleRunnerPlugin {}
 
 impl Plugin for ScheduleRunnerPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        let settings = app
-            .resources_mut()
-            .get_or_insert_with(ScheduleRunnerSettings::default)
-            .to_owned();
-        app.set_runner(move |mut app: App| {
+        app.resources_mut()
+            .get_or_insert_with(ScheduleRunnerSettings::default);
+        // apps built without the default stages can't update events, so they don't get overrun events either
+        if app.app.schedule.stage(stage::EVENT).is_some() {
+            app.add_event::<ScheduleOverrun>();
+        }
+        app.init_resource::<RunnerWaker>();
+        app.set_runner(|mut app: App| {
             app.initialize();
 
+            let settings = app
+                .resources
+                .get_cloned::<ScheduleRunnerSettings>()
+                .unwrap_or_default();
+            let mut post_update_hook = app.resources.remove::<PostUpdateHook>();
+            let mut update = move |app: &mut App| {
+                app.update();
+                if let Some(hook) = post_update_hook.as_mut() {
+                    hook.run(app);
+                }
+            };
+
             let mut app_exit_event_reader = EventReader::<AppExit>::default();
             match settings.run_mode {
                 RunMode::Once => {
-                    app.update();
+                    update(&mut app);
+                    app.shutdown().exit_process_on_failure();
                 }
-                RunMode::Loop { wait } => {
+                RunMode::OnSignal { timeout } => {
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        let _ = timeout;
+                        panic!("RunMode::OnSignal is not supported on wasm");
+                    }
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        let waker = app
+                            .resources
+                            .get_or_insert_with(RunnerWaker::default)
+                            .clone();
+                        loop {
+                            update(&mut app);
+
+                            let app_exit = app.resources.get::<Events<AppExit>>().and_then(
+                                |app_exit_events| {
+                                    app_exit_event_reader.latest(&app_exit_events).copied()
+                                },
+                            );
+                            if app_exit.is_some() {
+                                app.shutdown().exit_process_on_failure();
+                                break;
+                            }
+
+                            waker.wait(timeout);
+                        }
+                    }
+                }
+                RunMode::Loop {
+                    wait,
+                    max_iterations,
+                } => {
+                    app.resources.insert(LoopIterations::default());
+                    if max_iterations == Some(0) {
+                        app.shutdown().exit_process_on_failure();
+                        return;
+                    }
+
+                    let overrun_frames = settings.overrun_frames;
+                    let mut consecutive_overruns = 0;
                     let mut tick = move |app: &mut App,
                                          wait: Option<Duration>|
                           -> Result<Option<Duration>, AppExit> {
@@ -76,25 +351,64 @@ impl Plugin for ScheduleRunnerPlugin {
 
                         if let Some(app_exit_events) = app.resources.get_mut::<Events<AppExit>>() {
                             if let Some(exit) = app_exit_event_reader.latest(&app_exit_events) {
-                                return Err(exit.clone());
+                                return Err(*exit);
                             }
                         }
 
-                        app.update();
+                        update(app);
 
+                        // exits requested during the last update take precedence over the iteration limit's success
                         if let Some(app_exit_events) = app.resources.get_mut::<Events<AppExit>>() {
                             if let Some(exit) = app_exit_event_reader.latest(&app_exit_events) {
-                                return Err(exit.clone());
+                                return Err(*exit);
                             }
                         }
 
+                        let iterations = {
+                            let mut iterations =
+                                app.resources.get_or_insert_with(LoopIterations::default);
+                            iterations.0 += 1;
+                            iterations.0
+                        };
+                        if max_iterations.map_or(false, |max| iterations >= max) {
+                            if let Some(mut app_exit_events) =
+                                app.resources.get_mut::<Events<AppExit>>()
+                            {
+                                app_exit_events.send(AppExit::success());
+                            }
+                            return Err(AppExit::success());
+                        }
+
                         let end_time = Instant::now();
 
+                        let wait = app
+                            .resources
+                            .get::<ScheduleWait>()
+                            .map(|schedule_wait| schedule_wait.0)
+                            .or(wait);
                         if let Some(wait) = wait {
                             let exe_time = end_time - start_time;
                             if exe_time < wait {
+                                consecutive_overruns = 0;
                                 return Ok(Some(wait - exe_time));
                             }
+                            if exe_time == wait {
+                                // finished right on time, so there's nothing to wait out but no overrun either
+                                consecutive_overruns = 0;
+                                return Ok(None);
+                            }
+
+                            consecutive_overruns += 1;
+                            if overrun_frames > 0 && consecutive_overruns >= overrun_frames {
+                                consecutive_overruns = 0;
+                                if let Some(mut overrun_events) =
+                                    app.resources.get_mut::<Events<ScheduleOverrun>>()
+                                {
+                                    overrun_events.send(ScheduleOverrun {
+                                        over_by: exe_time - wait,
+                                    });
+                                }
+                            }
                         }
 
                         Ok(None)
@@ -102,9 +416,27 @@ impl Plugin for ScheduleRunnerPlugin {
 
                     #[cfg(not(target_arch = "wasm32"))]
                     {
-                        while let Ok(delay) = tick(&mut app, wait) {
-                            if let Some(delay) = delay {
-                                thread::sleep(delay);
+                        let mut frame_pacer = FramePacer::default();
+                        loop {
+                            match tick(&mut app, wait) {
+                                Ok(Some(delay)) => {
+                                    let wait_start = Instant::now();
+                                    settings.wait_strategy.wait(delay, &mut frame_pacer);
+                                    let waited = wait_start.elapsed();
+                                    let jitter = if waited > delay {
+                                        waited - delay
+                                    } else {
+                                        delay - waited
+                                    };
+                                    app.resources
+                                        .get_or_insert_with(FramePacingStats::default)
+                                        .record(jitter);
+                                }
+                                Ok(None) => {}
+                                Err(_) => {
+                                    app.shutdown().exit_process_on_failure();
+                                    break;
+                                }
                             }
                         }
                     }
@@ -133,7 +465,9 @@ impl Plugin for ScheduleRunnerPlugin {
                                 Ok(delay) => {
                                     set_timeout(f.borrow().as_ref().unwrap(), delay.unwrap_or(asap))
                                 }
-                                Err(_) => {}
+                                Err(_) => {
+                                    app.shutdown();
+                                }
                             }
                         };
                         *g.borrow_mut() = Some(Closure::wrap(Box::new(c) as Box<dyn FnMut()>));
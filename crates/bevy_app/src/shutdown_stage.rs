@@ -0,0 +1,2 @@
+/// Name of app stage that runs once when an app shuts down
+pub const SHUTDOWN: &str = "shutdown";
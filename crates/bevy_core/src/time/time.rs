@@ -15,6 +15,8 @@ pub struct Time {
     pub delta_seconds: f32,
     pub seconds_since_startup: f64,
     pub startup: Instant,
+    /// The number of times [Time::update] has been called, i.e. the number of frames that have started
+    pub frame_count: u64,
 }
 
 impl Default for Time {
@@ -26,6 +28,7 @@ impl Default for Time {
             delta_seconds_f64: 0.0,
             seconds_since_startup: 0.0,
             delta_seconds: 0.0,
+            frame_count: 0,
         }
     }
 }
@@ -42,6 +45,7 @@ impl Time {
         let duration_since_startup = now - self.startup;
         self.seconds_since_startup = duration_since_startup.as_secs_f64();
         self.instant = Some(now);
+        self.frame_count += 1;
     }
 
     pub fn time_since_startup(&self) -> Duration {
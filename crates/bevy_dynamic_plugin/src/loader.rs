@@ -1,27 +1,82 @@
 use libloading::{Library, Symbol};
 
 use bevy_app::{AppBuilder, CreatePlugin, Plugin};
+use std::{fs, path::Path};
 
 /// Dynamically links a plugin a the given path. The plugin must export the [CreatePlugin] function.
 pub fn dynamically_load_plugin(path: &str) -> (Library, Box<dyn Plugin>) {
-    let lib = Library::new(path).unwrap();
+    try_dynamically_load_plugin(path).unwrap()
+}
+
+/// Like [dynamically_load_plugin], but returns an error if the library can't be opened or doesn't export the
+/// [CreatePlugin] function.
+pub fn try_dynamically_load_plugin<P: AsRef<Path>>(
+    path: P,
+) -> Result<(Library, Box<dyn Plugin>), libloading::Error> {
+    let lib = Library::new(path.as_ref())?;
 
     unsafe {
-        let func: Symbol<CreatePlugin> = lib.get(b"_create_plugin").unwrap();
+        let func: Symbol<CreatePlugin> = lib.get(b"_create_plugin")?;
         let plugin = Box::from_raw(func());
-        (lib, plugin)
+        Ok((lib, plugin))
     }
 }
 
+/// Loads plugins from shared libraries. Libraries loaded this way are never unloaded, because the systems and resources
+/// the plugins add keep using the library's code until the app is dropped.
 pub trait DynamicPluginExt {
     fn load_plugin(&mut self, path: &str) -> &mut Self;
+
+    /// Loads and builds every shared library in `dir` that exports the [CreatePlugin] function. Files that fail
+    /// to load are logged and skipped.
+    fn load_plugins_from_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self;
 }
 
 impl DynamicPluginExt for AppBuilder {
     fn load_plugin(&mut self, path: &str) -> &mut Self {
-        let (_lib, plugin) = dynamically_load_plugin(path);
-        log::debug!("loaded plugin: {}", plugin.name());
-        plugin.build(self);
+        let (lib, plugin) = dynamically_load_plugin(path);
+        build_dynamic_plugin(self, lib, plugin);
         self
     }
+
+    fn load_plugins_from_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        let dir = dir.as_ref();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(err) => {
+                log::warn!("failed to read plugin directory {}: {}", dir.display(), err);
+                return self;
+            }
+        };
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.is_file()
+                    && path.extension().map_or(false, |extension| {
+                        extension == std::env::consts::DLL_EXTENSION
+                    })
+            })
+            .collect::<Vec<_>>();
+        // load in a stable order regardless of how the platform lists the directory
+        paths.sort();
+
+        for path in paths {
+            match try_dynamically_load_plugin(&path) {
+                Ok((lib, plugin)) => build_dynamic_plugin(self, lib, plugin),
+                Err(err) => log::warn!("skipping plugin {}: {}", path.display(), err),
+            }
+        }
+
+        self
+    }
+}
+
+/// Builds `plugin` and leaks `lib`, so the library is never unloaded. Systems, resources and components created by the
+/// plugin keep pointing into the library's code (their vtables and drop glue) until the app is dropped, and the app
+/// drops them in no particular order, so there is no point at which unloading the library would be safe.
+fn build_dynamic_plugin(app: &mut AppBuilder, lib: Library, plugin: Box<dyn Plugin>) {
+    log::debug!("loaded plugin: {}", plugin.name());
+    plugin.build(app);
+    std::mem::forget(lib);
 }
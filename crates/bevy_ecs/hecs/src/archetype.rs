@@ -369,8 +369,14 @@ impl Archetype {
         }
     }
 
+    /// Moves the components at `index` out of this archetype by passing each one to `f`, which takes ownership of it.
     /// Returns the ID of the entity moved into `index`, if any
-    pub(crate) unsafe fn move_to(
+    ///
+    /// # Safety
+    ///
+    ///  - `index` must be in-bound
+    ///  - `f` must take ownership of (or drop) each component it is given
+    pub unsafe fn move_to(
         &mut self,
         index: usize,
         mut f: impl FnMut(*mut u8, TypeId, usize, bool, bool),
@@ -535,6 +541,19 @@ impl TypeInfo {
         self.layout
     }
 
+    /// The name of the component type. Only tracked in debug builds, `None` otherwise.
+    #[inline]
+    pub fn type_name(&self) -> Option<&'static str> {
+        #[cfg(debug_assertions)]
+        {
+            Some(self.type_name)
+        }
+        #[cfg(not(debug_assertions))]
+        {
+            None
+        }
+    }
+
     pub(crate) unsafe fn drop(&self, data: *mut u8) {
         (self.drop)(data)
     }
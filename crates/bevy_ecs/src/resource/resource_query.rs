@@ -35,6 +35,34 @@ impl<'a, T: Resource> Deref for ChangedRes<'a, T> {
     }
 }
 
+/// A shared borrow of a Resource
+/// that will only return in a query once the Resource exists. Systems that use this are skipped until the Resource is
+/// inserted, which is useful for resources that are created later (for example, after an asset finishes loading).
+#[derive(Debug)]
+pub struct ExistingRes<'a, T: Resource> {
+    value: &'a T,
+}
+
+impl<'a, T: Resource> ExistingRes<'a, T> {
+    /// Creates a reference cell to a Resource from a pointer
+    ///
+    /// # Safety
+    /// The pointer must have correct lifetime / storage
+    pub unsafe fn new(value: NonNull<T>) -> Self {
+        Self {
+            value: &*value.as_ptr(),
+        }
+    }
+}
+
+impl<'a, T: Resource> Deref for ExistingRes<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
 /// Shared borrow of a Resource
 #[derive(Debug)]
 pub struct Res<'a, T: Resource> {
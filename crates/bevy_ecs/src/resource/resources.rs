@@ -5,6 +5,7 @@ use core::any::TypeId;
 use downcast_rs::{impl_downcast, Downcast};
 use std::{
     fmt::Debug,
+    mem::MaybeUninit,
     ops::{Deref, DerefMut},
     ptr::NonNull,
     thread::ThreadId,
@@ -121,10 +122,64 @@ impl Resources {
         }
     }
 
+    /// Removes the resource of type `T` and returns it, if it exists. System-local resources of the same type are kept.
+    pub fn remove<T: Resource>(&mut self) -> Option<T> {
+        let data = self.resource_data.get_mut(&TypeId::of::<T>())?;
+        let index = data.default_index.take()?;
+        let last = data.archetype.len() - 1;
+        let mut resource = MaybeUninit::<T>::uninit();
+        unsafe {
+            data.archetype
+                .move_to(index, |component, _type_id, size, _added, _mutated| {
+                    std::ptr::copy_nonoverlapping(
+                        component,
+                        resource.as_mut_ptr().cast::<u8>(),
+                        size,
+                    );
+                });
+        }
+
+        // the last resource in the archetype was swapped into the removed resource's slot
+        if index != last {
+            for archetype_index in data.system_id_to_archetype_index.values_mut() {
+                if *archetype_index == last {
+                    *archetype_index = index;
+                }
+            }
+        }
+
+        Some(unsafe { resource.assume_init() })
+    }
+
+    /// Inserts `resource` while `func` runs. Afterwards the resource is removed and any resource of the same type that
+    /// existed before is restored, even if `func` panics.
+    ///
+    /// The restored resource is re-inserted, so it counts as added until trackers are next cleared, and change
+    /// detection (ex: `ChangedRes`) will see it as changed.
+    pub fn scope<T: Resource, R>(
+        &mut self,
+        resource: T,
+        func: impl FnOnce(&mut Resources) -> R,
+    ) -> R {
+        let previous = self.remove::<T>();
+        self.insert(resource);
+        let mut guard = ScopeGuard {
+            resources: self,
+            previous,
+        };
+        func(&mut *guard.resources)
+    }
+
     pub fn contains<T: Resource>(&self) -> bool {
         self.get_resource::<T>(ResourceIndex::Global).is_some()
     }
 
+    /// Returns true if the resource exists at the given index. Unlike [Resources::contains], this doesn't borrow the
+    /// resource, so it can be used while systems hold unchecked references to it.
+    pub fn contains_index<T: Resource>(&self, resource_index: ResourceIndex) -> bool {
+        self.get_resource_data_index::<T>(resource_index).is_some()
+    }
+
     pub fn get<T: Resource>(&self) -> Option<Ref<'_, T>> {
         self.get_resource(ResourceIndex::Global)
     }
@@ -364,6 +419,21 @@ impl Resources {
 unsafe impl Send for Resources {}
 unsafe impl Sync for Resources {}
 
+/// Restores the previous resource of type `T` when a [Resources::scope] ends
+struct ScopeGuard<'a, T: Resource> {
+    resources: &'a mut Resources,
+    previous: Option<T>,
+}
+
+impl<'a, T: Resource> Drop for ScopeGuard<'a, T> {
+    fn drop(&mut self) {
+        self.resources.remove::<T>();
+        if let Some(previous) = self.previous.take() {
+            self.resources.insert(previous);
+        }
+    }
+}
+
 /// Creates `Self` using data from the `Resources` collection
 pub trait FromResources {
     /// Creates `Self` using data from the `Resources` collection
@@ -542,6 +612,65 @@ mod tests {
         assert_eq!(*resources.get::<i32>().expect("resource exists"), 123);
     }
 
+    #[test]
+    fn remove_resource() {
+        let mut resources = Resources::default();
+        assert!(resources.remove::<i32>().is_none());
+
+        resources.insert(123);
+        resources.insert_local(SystemId(0), 111);
+        resources.insert_local(SystemId(1), 222);
+        assert_eq!(resources.remove::<i32>(), Some(123));
+        assert!(!resources.contains::<i32>());
+        assert!(resources.remove::<i32>().is_none());
+        assert_eq!(
+            *resources
+                .get_local::<i32>(SystemId(0))
+                .expect("resource exists"),
+            111
+        );
+        assert_eq!(
+            *resources
+                .get_local::<i32>(SystemId(1))
+                .expect("resource exists"),
+            222
+        );
+
+        resources.insert(456);
+        assert_eq!(*resources.get::<i32>().expect("resource exists"), 456);
+        assert_eq!(
+            *resources
+                .get_local::<i32>(SystemId(1))
+                .expect("resource exists"),
+            222
+        );
+    }
+
+    #[test]
+    fn scoped_resource() {
+        let mut resources = Resources::default();
+        let value = resources.scope(123, |resources| *resources.get::<i32>().unwrap());
+        assert_eq!(value, 123);
+        assert!(!resources.contains::<i32>());
+
+        resources.insert(456);
+        resources.scope(789, |resources| {
+            assert_eq!(*resources.get::<i32>().unwrap(), 789);
+        });
+        assert_eq!(*resources.get::<i32>().unwrap(), 456);
+    }
+
+    #[test]
+    fn scoped_resource_restored_after_panic() {
+        let mut resources = Resources::default();
+        resources.insert(456);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            resources.scope(789, |_resources| panic!("scope panicked"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(*resources.get::<i32>().unwrap(), 456);
+    }
+
     #[test]
     #[should_panic(expected = "i32 already borrowed")]
     fn resource_double_mut_panic() {
@@ -1,6 +1,10 @@
 mod parallel_executor;
 #[allow(clippy::module_inception)]
 mod schedule;
+mod stage;
+mod system_stats;
 
 pub use parallel_executor::*;
 pub use schedule::*;
+pub use stage::*;
+pub use system_stats::*;
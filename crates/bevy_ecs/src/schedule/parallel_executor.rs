@@ -1,12 +1,23 @@
-use super::Schedule;
+use super::{Schedule, Stage, SystemStats};
 use crate::{
     resource::Resources,
-    system::{System, ThreadLocalExecution},
+    system::{Profiler, System, SystemId, ThreadLocalExecution},
 };
 use bevy_hecs::{ArchetypesGeneration, TypeAccess, World};
 use bevy_tasks::{ComputeTaskPool, CountdownEvent, TaskPool};
+use bevy_utils::HashSet;
 use fixedbitset::FixedBitSet;
-use std::ops::Range;
+#[cfg(target_arch = "wasm32")]
+use instant::Instant;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+use std::{
+    any::Any,
+    borrow::Cow,
+    ops::Range,
+    panic::{self, AssertUnwindSafe},
+    time::Duration,
+};
 #[cfg(feature = "trace")]
 use tracing::info_span;
 
@@ -17,12 +28,24 @@ use tracing::info_span;
 /// * in a given stage, systems the read [archetype+component] X cannot run before systems registered before them that write [archetype+component] X
 /// * in a given stage, systems that mutate resource Y cannot run before systems registered before them that read/write resource Y
 /// * in a given stage, systems the read resource Y cannot run before systems registered before them that write resource Y
+///
+/// Deferred world changes (such as [Commands](crate::Commands)) are applied as follows:
+/// * [ThreadLocalExecution::Immediate] systems run exclusively, after every system registered before them in the stage has finished
+///   and before any system registered after them starts. Their thread local logic runs right after their regular logic.
+/// * [ThreadLocalExecution::NextFlush] systems have their thread local logic (for example, their queued [Commands](crate::Commands))
+///   applied at the end of the stage, one system at a time, in the order the systems were registered in the stage. This happens
+///   regardless of the order the systems actually ran in, so the flush of a stage is deterministic.
 
 #[derive(Debug)]
 pub struct ParallelExecutor {
     stages: Vec<ExecutorStage>,
     last_schedule_generation: usize,
+    /// the [Schedule::force_sequential] setting during the last run. Switching modes rebuilds the executor stages.
+    last_force_sequential: bool,
     clear_trackers: bool,
+    catch_system_panics: bool,
+    system_panics: Vec<SystemPanic>,
+    panicked_systems: HashSet<SystemId>,
 }
 
 impl Default for ParallelExecutor {
@@ -30,11 +53,23 @@ impl Default for ParallelExecutor {
         Self {
             stages: Default::default(),
             last_schedule_generation: usize::MAX, // MAX forces prepare to run the first time
+            last_force_sequential: false,
             clear_trackers: true,
+            catch_system_panics: false,
+            system_panics: Default::default(),
+            panicked_systems: Default::default(),
         }
     }
 }
 
+/// A panic that was caught while running a system. See [ParallelExecutor::set_catch_system_panics].
+#[derive(Debug, Clone)]
+pub struct SystemPanic {
+    pub system: SystemId,
+    pub name: Cow<'static, str>,
+    pub message: String,
+}
+
 impl ParallelExecutor {
     pub fn without_tracker_clears() -> Self {
         Self {
@@ -43,6 +78,33 @@ impl ParallelExecutor {
         }
     }
 
+    /// When enabled, a panic inside a system is caught instead of unwinding through the executor. The panic is recorded as
+    /// a [SystemPanic] (see [ParallelExecutor::drain_system_panics]) and the system is skipped on every following run.
+    /// A system that panicked does not have its thread local logic (for example, its queued commands) applied.
+    pub fn set_catch_system_panics(&mut self, catch_system_panics: bool) {
+        self.catch_system_panics = catch_system_panics;
+    }
+
+    /// Returns the panics caught since the last call to this function
+    pub fn drain_system_panics(&mut self) -> impl Iterator<Item = SystemPanic> + '_ {
+        self.system_panics.drain(..)
+    }
+
+    /// Returns true if the system with the given id panicked and is now skipped
+    pub fn is_system_panicked(&self, system_id: SystemId) -> bool {
+        self.panicked_systems.contains(&system_id)
+    }
+
+    /// Returns true if any system panicked and is still skipped
+    pub fn has_panicked_systems(&self) -> bool {
+        !self.panicked_systems.is_empty()
+    }
+
+    /// Allows a system that panicked to run again
+    pub fn resume_system(&mut self, system_id: SystemId) {
+        self.panicked_systems.remove(&system_id);
+    }
+
     pub fn initialize(&mut self, resources: &mut Resources) {
         if resources.get::<ComputeTaskPool>().is_none() {
             resources.insert(ComputeTaskPool(TaskPool::default()));
@@ -56,7 +118,16 @@ impl ParallelExecutor {
         let _schedule_guard = schedule_span.enter();
 
         let schedule_generation = schedule.generation();
-        let schedule_changed = schedule.generation() != self.last_schedule_generation;
+        let force_sequential = schedule.force_sequential();
+        // the sequential path doesn't prepare executor stages, so switching back to parallel has to rebuild them
+        let schedule_changed = schedule.generation() != self.last_schedule_generation
+            || force_sequential != self.last_force_sequential;
+        let log_stages = schedule.log_stages();
+        let record_stats = resources
+            .get::<SystemStats>()
+            .map_or(false, |stats| stats.enabled);
+        // checked once per run so that systems don't look up the profiler when there is none
+        let profile = resources.contains::<Box<dyn Profiler>>();
         if schedule_changed {
             self.stages.clear();
             self.stages
@@ -69,24 +140,80 @@ impl ParallelExecutor {
             #[cfg(feature = "trace")]
             let _stage_guard = stage_span.enter();
             log::trace!("run stage {:?}", stage_name);
-            if let Some(stage_systems) = schedule.stages.get_mut(stage_name) {
-                executor_stage.run(world, resources, stage_systems, schedule_changed);
+            if let Some(stage) = schedule.stages.get_mut(stage_name) {
+                if log_stages {
+                    log::debug!("stage {} started ({} systems)", stage_name, stage.len());
+                }
+
+                if schedule_changed {
+                    executor_stage.run_after = stage.run_after_indices();
+                }
+
+                let stage_systems = &mut stage.systems;
+                if force_sequential {
+                    executor_stage.run_sequential(
+                        world,
+                        resources,
+                        stage_systems,
+                        record_stats,
+                        profile,
+                        self.catch_system_panics,
+                        &self.panicked_systems,
+                    );
+                } else {
+                    executor_stage.run(
+                        world,
+                        resources,
+                        stage_systems,
+                        schedule_changed,
+                        record_stats,
+                        profile,
+                        self.catch_system_panics,
+                        &self.panicked_systems,
+                    );
+                }
+
+                let mut stats = if record_stats {
+                    resources.get_mut::<SystemStats>()
+                } else {
+                    None
+                };
+                for report in executor_stage.system_reports.drain(..) {
+                    let system = &stage_systems[report.system_index];
+                    if let (Some(stats), Some(duration)) = (stats.as_mut(), report.duration) {
+                        stats.record(system.id(), system.name(), duration);
+                    }
+                    if let Some(message) = report.panic_message {
+                        log::error!("system {} panicked: {}", system.name(), message);
+                        self.panicked_systems.insert(system.id());
+                        self.system_panics.push(SystemPanic {
+                            system: system.id(),
+                            name: system.name(),
+                            message,
+                        });
+                    }
+                }
+
+                if log_stages {
+                    log::debug!("stage {} finished", stage_name);
+                }
             }
         }
 
-        if self.clear_trackers {
+        if self.clear_trackers && schedule.clear_trackers_after_run() {
             world.clear_trackers();
             resources.clear_trackers();
         }
 
         self.last_schedule_generation = schedule_generation;
+        self.last_force_sequential = force_sequential;
     }
 
     pub fn print_order(&self, schedule: &Schedule) {
         println!("----------------------------");
         for (stage_name, executor_stage) in schedule.stage_order.iter().zip(self.stages.iter()) {
             println!("stage {:?}", stage_name);
-            if let Some(stage_systems) = schedule.stages.get(stage_name) {
+            if let Some(stage_systems) = schedule.stages.get(stage_name).map(Stage::systems) {
                 for (i, system) in stage_systems.iter().enumerate() {
                     println!("  {}-{}", i, system.name());
                     println!(
@@ -122,6 +249,18 @@ pub struct ExecutorStage {
     /// When archetypes change a counter is bumped - we cache the state of that counter when it was
     /// last read here so that we can detect when archetypes are changed
     last_archetypes_generation: ArchetypesGeneration,
+    /// the run duration and caught panic (if any) of each system that ran during the last run
+    system_reports: Vec<SystemRunReport>,
+    /// the index of the system each system has to run after, regardless of data access
+    run_after: Vec<Option<usize>>,
+}
+
+/// The outcome of a single system run. Only produced when timings were requested or the system panicked.
+#[derive(Debug, Clone)]
+pub struct SystemRunReport {
+    pub system_index: usize,
+    pub duration: Option<Duration>,
+    pub panic_message: Option<String>,
 }
 
 impl Default for ExecutorStage {
@@ -134,6 +273,8 @@ impl Default for ExecutorStage {
             system_dependencies: Default::default(),
             thread_local_system_indices: Default::default(),
             last_archetypes_generation: ArchetypesGeneration(u64::MAX), // MAX forces prepare to run the first time
+            system_reports: Default::default(),
+            run_after: Default::default(),
         }
     }
 }
@@ -236,6 +377,21 @@ impl ExecutorStage {
                             self.system_dependents[last_thread_local_index].push(system_index);
                             self.system_dependencies[system_index].insert(last_thread_local_index);
                         }
+
+                        // systems in sequential sets also depend on the system listed before them. earlier batches
+                        // have already finished by the time this batch runs, so only this batch needs an edge
+                        if let Some(earlier_system_index) =
+                            self.run_after.get(system_index).copied().flatten()
+                        {
+                            if earlier_system_index >= prepare_system_index_range.start
+                                && earlier_system_index < system_index
+                                && !self.system_dependencies[system_index]
+                                    .contains(earlier_system_index)
+                            {
+                                self.system_dependents[earlier_system_index].push(system_index);
+                                self.system_dependencies[system_index].insert(earlier_system_index);
+                            }
+                        }
                     }
                     ThreadLocalExecution::Immediate => {
                         for earlier_system_index in prepare_system_index_range.start..system_index {
@@ -324,7 +480,10 @@ impl ExecutorStage {
         }
     }
 
-    /// Runs the non-thread-local systems in the given prepared_system_range range
+    /// Runs the non-thread-local systems in the given prepared_system_range range. Returns a report for each system that
+    /// ran while `record_timings` is true or that panicked while `catch_panics` is true. Systems in `skipped_systems` are
+    /// not run, but still unblock their dependents.
+    #[allow(clippy::too_many_arguments)]
     pub fn run_systems(
         &self,
         world: &World,
@@ -332,10 +491,20 @@ impl ExecutorStage {
         systems: &mut [Box<dyn System>],
         prepared_system_range: Range<usize>,
         compute_pool: &TaskPool,
-    ) {
+        record_timings: bool,
+        profile: bool,
+        catch_panics: bool,
+        skipped_systems: &HashSet<SystemId>,
+    ) -> Vec<SystemRunReport> {
         // Generate tasks for systems in the given range and block until they are complete
         log::trace!("running systems {:?}", prepared_system_range);
-        compute_pool.scope(|scope| {
+        let profiler = if profile {
+            resources.get::<Box<dyn Profiler>>()
+        } else {
+            None
+        };
+        let profiler = profiler.as_deref().map(|profiler| &**profiler);
+        let reports = compute_pool.scope(|scope| {
             let start_system_index = prepared_system_range.start;
             let mut system_index = start_system_index;
             for system in &mut systems[prepared_system_range] {
@@ -402,39 +571,61 @@ impl ExecutorStage {
 
                     // Execute the system - in a scope to ensure the system lock is dropped before
                     // triggering dependents
-                    {
+                    let report = if skipped_systems.contains(&system.id()) {
+                        log::trace!("skip panicked system {}", system.name());
+                        None
+                    } else {
                         #[cfg(feature = "trace")]
                         let system_span = info_span!("system", name = system.name().as_ref());
                         #[cfg(feature = "trace")]
                         let _system_guard = system_span.enter();
 
                         log::trace!("run {}", system.name());
-                        #[cfg(feature = "profiler")]
-                        crate::profiler_start(resources, system.name().clone());
-                        system.run(world_ref, resources_ref);
-                        #[cfg(feature = "profiler")]
-                        crate::profiler_stop(resources, system.name().clone());
-                    }
+                        if let Some(profiler) = profiler {
+                            profiler.start(system.name());
+                        }
+                        let start = if record_timings {
+                            Some(Instant::now())
+                        } else {
+                            None
+                        };
+                        let panic_message =
+                            run_guarded(catch_panics, || system.run(world_ref, resources_ref));
+                        if let Some(profiler) = profiler {
+                            profiler.stop(system.name());
+                        }
+                        SystemRunReport::new(system_index, start, panic_message)
+                    };
 
                     // Notify dependents that this task is done
                     for trigger_event in trigger_events {
                         trigger_event.decrement();
                     }
+
+                    report
                 });
                 system_index += 1;
             }
         });
+
+        reports.into_iter().flatten().collect()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn run(
         &mut self,
         world: &mut World,
         resources: &mut Resources,
         systems: &mut [Box<dyn System>],
         schedule_changed: bool,
+        record_timings: bool,
+        profile: bool,
+        catch_panics: bool,
+        skipped_systems: &HashSet<SystemId>,
     ) {
         let start_archetypes_generation = world.archetypes_generation();
         let compute_pool = resources.get_cloned::<ComputeTaskPool>().unwrap();
+        self.system_reports.clear();
 
         // if the schedule has changed, clear executor state / fill it with new defaults
         // This is mostly zeroing out a bunch of arrays parallel to the systems array. They will get
@@ -483,13 +674,18 @@ impl ExecutorStage {
             );
 
             // Run everything up to the thread local system
-            self.run_systems(
+            let reports = self.run_systems(
                 world,
                 resources,
                 systems,
                 prepared_system_range,
                 &*compute_pool,
+                record_timings,
+                profile,
+                catch_panics,
+                skipped_systems,
             );
+            self.system_reports.extend(reports);
         }
 
         loop {
@@ -505,14 +701,34 @@ impl ExecutorStage {
                 // if a thread local system is ready to run, run it exclusively on the main thread
                 let system = systems[thread_local_system_index].as_mut();
 
-                #[cfg(feature = "trace")]
-                let system_span = info_span!("system", name = system.name().as_ref());
-                #[cfg(feature = "trace")]
-                let _system_guard = system_span.enter();
+                if !skipped_systems.contains(&system.id()) {
+                    #[cfg(feature = "trace")]
+                    let system_span = info_span!("system", name = system.name().as_ref());
+                    #[cfg(feature = "trace")]
+                    let _system_guard = system_span.enter();
 
-                log::trace!("running thread local system {}", system.name());
-                system.run(world, resources);
-                system.run_thread_local(world, resources);
+                    log::trace!("running thread local system {}", system.name());
+                    if profile {
+                        crate::profiler_start(resources, system.name());
+                    }
+                    let start = if record_timings {
+                        Some(Instant::now())
+                    } else {
+                        None
+                    };
+                    let panic_message = run_guarded(catch_panics, || {
+                        system.run(world, resources);
+                        system.run_thread_local(world, resources);
+                    });
+                    if profile {
+                        crate::profiler_stop(resources, system.name());
+                    }
+                    if let Some(report) =
+                        SystemRunReport::new(thread_local_system_index, start, panic_message)
+                    {
+                        self.system_reports.push(report);
+                    }
+                }
             }
 
             // Now that the previous thread local system has run, time to advance to the next one
@@ -528,34 +744,159 @@ impl ExecutorStage {
             );
 
             log::trace!("running systems {:?}", run_ready_system_index_range);
-            self.run_systems(
+            let reports = self.run_systems(
                 world,
                 resources,
                 systems,
                 run_ready_system_index_range,
                 &*compute_pool,
+                record_timings,
+                profile,
+                catch_panics,
+                skipped_systems,
             );
+            self.system_reports.extend(reports);
+        }
+
+        self.flush(world, resources, systems, catch_panics, skipped_systems);
+
+        // If world's archetypes_generation is the same as it was before running any systems then
+        // we can assume that all systems have correct archetype accesses.
+        if start_archetypes_generation == world.archetypes_generation() {
+            self.last_archetypes_generation = world.archetypes_generation();
+        }
+    }
+
+    /// Runs each system one at a time on the calling thread, in the order they were added, followed by the thread local
+    /// flush. Like [ExecutorStage::run], this skips `skipped_systems`, catches panics and records timings when asked to.
+    #[allow(clippy::too_many_arguments)]
+    pub fn run_sequential(
+        &mut self,
+        world: &mut World,
+        resources: &mut Resources,
+        systems: &mut [Box<dyn System>],
+        record_timings: bool,
+        profile: bool,
+        catch_panics: bool,
+        skipped_systems: &HashSet<SystemId>,
+    ) {
+        self.system_reports.clear();
+        for (system_index, system) in systems.iter_mut().enumerate() {
+            if skipped_systems.contains(&system.id()) {
+                log::trace!("skip panicked system {}", system.name());
+                continue;
+            }
+
+            #[cfg(feature = "trace")]
+            let system_span = info_span!("system", name = system.name().as_ref());
+            #[cfg(feature = "trace")]
+            let _system_guard = system_span.enter();
+
+            system.update(world);
+            if profile {
+                crate::profiler_start(resources, system.name());
+            }
+            let start = if record_timings {
+                Some(Instant::now())
+            } else {
+                None
+            };
+            let panic_message = run_guarded(catch_panics, || {
+                system.run(world, resources);
+                if system.thread_local_execution() == ThreadLocalExecution::Immediate {
+                    system.run_thread_local(world, resources);
+                }
+            });
+            if profile {
+                crate::profiler_stop(resources, system.name());
+            }
+            if let Some(report) = SystemRunReport::new(system_index, start, panic_message) {
+                self.system_reports.push(report);
+            }
         }
 
-        // "flush"
-        for system in systems.iter_mut() {
+        self.flush(world, resources, systems, catch_panics, skipped_systems);
+    }
+
+    /// Applies the thread local logic of every [ThreadLocalExecution::NextFlush] system, in the order they were added
+    fn flush(
+        &mut self,
+        world: &mut World,
+        resources: &mut Resources,
+        systems: &mut [Box<dyn System>],
+        catch_panics: bool,
+        skipped_systems: &HashSet<SystemId>,
+    ) {
+        for (system_index, system) in systems.iter_mut().enumerate() {
             match system.thread_local_execution() {
                 ThreadLocalExecution::NextFlush => {
+                    // systems that panicked (now or in an earlier run) may have left their thread local state half
+                    // written, so it isn't applied
+                    if skipped_systems.contains(&system.id())
+                        || self.system_reports.iter().any(|report| {
+                            report.system_index == system_index && report.panic_message.is_some()
+                        })
+                    {
+                        continue;
+                    }
+
                     #[cfg(feature = "trace")]
                     let system_span = info_span!("system", name = system.name().as_ref());
                     #[cfg(feature = "trace")]
                     let _system_guard = system_span.enter();
-                    system.run_thread_local(world, resources);
+                    if let Some(panic_message) =
+                        run_guarded(catch_panics, || system.run_thread_local(world, resources))
+                    {
+                        self.system_reports.push(SystemRunReport {
+                            system_index,
+                            duration: None,
+                            panic_message: Some(panic_message),
+                        });
+                    }
                 }
                 ThreadLocalExecution::Immediate => { /* already ran */ }
             }
         }
+    }
+}
 
-        // If world's archetypes_generation is the same as it was before running any systems then
-        // we can assume that all systems have correct archetype accesses.
-        if start_archetypes_generation == world.archetypes_generation() {
-            self.last_archetypes_generation = world.archetypes_generation();
+impl SystemRunReport {
+    fn new(
+        system_index: usize,
+        start: Option<Instant>,
+        panic_message: Option<String>,
+    ) -> Option<Self> {
+        if start.is_none() && panic_message.is_none() {
+            return None;
         }
+
+        Some(SystemRunReport {
+            system_index,
+            duration: start.map(|start| start.elapsed()),
+            panic_message,
+        })
+    }
+}
+
+/// Runs `func`, catching any panic if `catch_panics` is true. Returns the panic's message if one was caught.
+fn run_guarded(catch_panics: bool, func: impl FnOnce()) -> Option<String> {
+    if !catch_panics {
+        func();
+        return None;
+    }
+
+    panic::catch_unwind(AssertUnwindSafe(func))
+        .err()
+        .map(panic_message)
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic payload".to_string()
     }
 }
 
@@ -564,15 +905,17 @@ mod tests {
     use super::ParallelExecutor;
     use crate::{
         resource::{Res, ResMut, Resources},
-        schedule::Schedule,
-        system::{IntoSystem, IntoThreadLocalSystem, Query},
+        schedule::{Schedule, SetOrdering, SystemSet, SystemStats},
+        system::{
+            IntoSystem, IntoThreadLocalSystem, Query, System, SystemId, ThreadLocalExecution,
+        },
         Commands,
     };
-    use bevy_hecs::{Entity, World};
+    use bevy_hecs::{ArchetypeComponent, Entity, TypeAccess, World};
     use bevy_tasks::{ComputeTaskPool, TaskPool};
     use fixedbitset::FixedBitSet;
     use parking_lot::Mutex;
-    use std::{collections::HashSet, sync::Arc};
+    use std::{any::TypeId, borrow::Cow, collections::HashSet, sync::Arc};
 
     #[derive(Default)]
     struct CompletedSystems {
@@ -878,4 +1221,385 @@ mod tests {
             run_executor_and_validate(&mut executor, &mut schedule, &mut world, &mut resources);
         }
     }
+
+    #[test]
+    fn system_stats() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(SystemStats::default());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+
+        fn parallel(_query: Query<&u32>) {}
+        fn thread_local(_world: &mut World, _resources: &mut Resources) {}
+
+        let parallel_system = parallel.system();
+        let parallel_id = parallel_system.id();
+        let thread_local_system = thread_local.thread_local_system();
+        let thread_local_id = thread_local_system.id();
+        schedule.add_system_to_stage("update", parallel_system);
+        schedule.add_system_to_stage("update", thread_local_system);
+        schedule.initialize(&mut world, &mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            resources.get::<SystemStats>().unwrap().iter().count(),
+            0,
+            "disabled stats should not record timings"
+        );
+
+        resources.get_mut::<SystemStats>().unwrap().enabled = true;
+        executor.run(&mut schedule, &mut world, &mut resources);
+        let stats = resources.get::<SystemStats>().unwrap();
+        assert!(stats.get(parallel_id).is_some());
+        assert!(stats.get(thread_local_id).is_some());
+        assert_eq!(stats.slowest(1).len(), 1);
+    }
+
+    #[test]
+    fn validate_access_conflicts() {
+        let mut world = World::new();
+        world.spawn((0u32, 0u64));
+
+        fn write_u32_a(_query: Query<&mut u32>) {}
+        fn write_u32_b(_query: Query<&mut u32>) {}
+
+        let write_a = write_u32_a.system();
+        let write_a_id = write_a.id();
+        let write_a_name = write_a.name();
+        let write_b = write_u32_b.system();
+        let write_b_id = write_b.id();
+        let write_b_name = write_b.name();
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", write_a);
+        schedule.add_system_to_stage("update", write_b);
+
+        let conflicts = schedule.validate(&world);
+        assert_eq!(conflicts.len(), 1);
+        let conflict = &conflicts[0];
+        assert_eq!(conflict.stage, "update");
+        assert_eq!(conflict.first_system, write_a_id);
+        assert_eq!(conflict.first_system_name, write_a_name);
+        assert_eq!(conflict.second_system, write_b_id);
+        assert_eq!(conflict.second_system_name, write_b_name);
+        assert_eq!(conflict.component, TypeId::of::<u32>());
+        if cfg!(debug_assertions) {
+            assert_eq!(conflict.component_name, Some(std::any::type_name::<u32>()));
+        }
+    }
+
+    #[test]
+    fn validate_thread_local_separates_conflicts() {
+        let mut world = World::new();
+        world.spawn((0u32,));
+
+        fn write_u32_a(_query: Query<&mut u32>) {}
+        fn thread_local(_world: &mut World, _resources: &mut Resources) {}
+        fn write_u32_b(_query: Query<&mut u32>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", write_u32_a.system());
+        schedule.add_system_to_stage("update", thread_local.thread_local_system());
+        schedule.add_system_to_stage("update", write_u32_b.system());
+
+        assert!(schedule.validate(&world).is_empty());
+    }
+
+    #[test]
+    fn validate_ignores_read_write_pairs() {
+        let mut world = World::new();
+        world.spawn((0u32,));
+
+        fn read_u32(_query: Query<&u32>) {}
+        fn write_u32(_query: Query<&mut u32>) {}
+        fn read_u32_again(_query: Query<&u32>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", read_u32.system());
+        schedule.add_system_to_stage("update", write_u32.system());
+        schedule.add_system_to_stage("update", read_u32_again.system());
+
+        assert!(schedule.validate(&world).is_empty());
+    }
+
+    /// A system that does nothing and always has the given id
+    #[derive(Default)]
+    struct FixedIdSystem {
+        id: usize,
+        archetype_component_access: TypeAccess<ArchetypeComponent>,
+        resource_access: TypeAccess<TypeId>,
+    }
+
+    impl FixedIdSystem {
+        fn new(id: usize) -> Box<dyn System> {
+            Box::new(FixedIdSystem {
+                id,
+                ..Default::default()
+            })
+        }
+    }
+
+    impl System for FixedIdSystem {
+        fn name(&self) -> Cow<'static, str> {
+            "fixed_id".into()
+        }
+
+        fn id(&self) -> SystemId {
+            SystemId(self.id)
+        }
+
+        fn is_initialized(&self) -> bool {
+            true
+        }
+
+        fn update(&mut self, _world: &World) {}
+
+        fn archetype_component_access(&self) -> &TypeAccess<ArchetypeComponent> {
+            &self.archetype_component_access
+        }
+
+        fn resource_access(&self) -> &TypeAccess<TypeId> {
+            &self.resource_access
+        }
+
+        fn thread_local_execution(&self) -> ThreadLocalExecution {
+            ThreadLocalExecution::NextFlush
+        }
+
+        fn run(&mut self, _world: &World, _resources: &Resources) {}
+
+        fn run_thread_local(&mut self, _world: &mut World, _resources: &mut Resources) {}
+    }
+
+    #[test]
+    fn same_system_id_in_different_stages() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("first");
+        schedule.add_stage("second");
+        schedule.add_system_to_stage("first", FixedIdSystem::new(1));
+        schedule.add_system_to_stage("second", FixedIdSystem::new(1));
+        assert_eq!(schedule.system_count(), 2);
+
+        schedule.initialize(&mut world, &mut resources);
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+    }
+
+    #[test]
+    #[should_panic(expected = "already exists in stage first")]
+    fn same_system_id_in_one_stage_panics() {
+        let mut schedule = Schedule::default();
+        schedule.add_stage("first");
+        schedule.add_system_to_stage("first", FixedIdSystem::new(1));
+        schedule.add_system_to_stage("first", FixedIdSystem::new(1));
+    }
+
+    #[test]
+    fn remove_system_set_after_rename_stage() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0u32);
+
+        fn count(mut count: ResMut<u32>) {
+            *count += 1;
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_set_to_stage(
+            "update",
+            SystemSet::new("counting").with_system(count.system()),
+        );
+        schedule.rename_stage("update", "renamed");
+
+        assert_eq!(schedule.remove_system_set("counting").len(), 1);
+        assert_eq!(schedule.system_count(), 0);
+
+        schedule.initialize(&mut world, &mut resources);
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(*resources.get::<u32>().unwrap(), 0);
+    }
+
+    #[test]
+    fn sequential_system_set() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0u32);
+
+        fn read_a(_value: Res<u32>) {}
+        fn read_b(_value: Res<u32>) {}
+        fn read_c(_value: Res<u32>) {}
+        fn read_d(_value: Res<u32>) {}
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", read_a.system());
+        let read_c = read_c.system();
+        let read_c_id = read_c.id();
+        schedule.add_system_set_to_stage(
+            "update",
+            SystemSet::new("ordered")
+                .with_system(read_b.system())
+                .with_system(read_c)
+                .with_system(read_d.system())
+                .with_ordering(SetOrdering::Sequential),
+        );
+        schedule.initialize(&mut world, &mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            executor.stages[0].system_dependents,
+            vec![vec![], vec![2], vec![3], vec![]],
+            "only the systems in the set are chained"
+        );
+
+        // removing a system from the middle of the chain keeps the rest of the chain in order
+        schedule.remove_system(read_c_id);
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(
+            executor.stages[0].system_dependents,
+            vec![vec![], vec![2], vec![]]
+        );
+    }
+
+    #[test]
+    fn catch_system_panics() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0u32);
+        resources.insert(0u64);
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+
+        fn panics(mut count: ResMut<u32>) {
+            *count += 1;
+            panic!("system failed");
+        }
+
+        fn counts(mut count: ResMut<u64>) {
+            *count += 1;
+        }
+
+        let panics_system = panics.system();
+        let panics_id = panics_system.id();
+        schedule.add_system_to_stage("update", panics_system);
+        schedule.add_system_to_stage("update", counts.system());
+        schedule.initialize(&mut world, &mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.set_catch_system_panics(true);
+        executor.run(&mut schedule, &mut world, &mut resources);
+        executor.run(&mut schedule, &mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<u32>().unwrap(),
+            1,
+            "panicked system is skipped"
+        );
+        assert_eq!(
+            *resources.get::<u64>().unwrap(),
+            2,
+            "other systems keep running"
+        );
+        assert!(executor.is_system_panicked(panics_id));
+        let system_panics = executor.drain_system_panics().collect::<Vec<_>>();
+        assert_eq!(system_panics.len(), 1);
+        assert_eq!(system_panics[0].system, panics_id);
+        assert_eq!(system_panics[0].message, "system failed");
+    }
+
+    #[test]
+    fn force_sequential() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(Vec::<usize>::new());
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.set_force_sequential(true);
+
+        fn first(mut order: ResMut<Vec<usize>>) {
+            order.push(1);
+        }
+
+        fn second(mut order: ResMut<Vec<usize>>) {
+            order.push(2);
+        }
+
+        fn third(world: &mut World, resources: &mut Resources) {
+            world.spawn((3u32,));
+            resources.get_mut::<Vec<usize>>().unwrap().push(3);
+        }
+
+        fn fourth(mut order: ResMut<Vec<usize>>, query: Query<&u32>) {
+            assert_eq!(query.iter().count(), 1);
+            order.push(4);
+        }
+
+        schedule.add_system_to_stage("update", first.system());
+        schedule.add_system_to_stage("update", second.system());
+        schedule.add_system_to_stage("update", third.thread_local_system());
+        schedule.add_system_to_stage("update", fourth.system());
+        schedule.initialize(&mut world, &mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(*resources.get::<Vec<usize>>().unwrap(), vec![1, 2, 3, 4]);
+
+        // switching back to parallel execution has to prepare the executor stages
+        schedule.set_force_sequential(false);
+        executor.run(&mut schedule, &mut world, &mut resources);
+        assert_eq!(resources.get::<Vec<usize>>().unwrap().len(), 8);
+    }
+
+    #[test]
+    fn force_sequential_catch_system_panics() {
+        let mut world = World::new();
+        let mut resources = Resources::default();
+        resources.insert(ComputeTaskPool(TaskPool::default()));
+        resources.insert(0u32);
+
+        fn panics(mut count: ResMut<u32>) {
+            *count += 1;
+            panic!("sequential panic");
+        }
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.set_force_sequential(true);
+        schedule.add_system_to_stage("update", panics.system());
+        schedule.initialize(&mut world, &mut resources);
+
+        let mut executor = ParallelExecutor::default();
+        executor.set_catch_system_panics(true);
+        executor.run(&mut schedule, &mut world, &mut resources);
+        executor.run(&mut schedule, &mut world, &mut resources);
+
+        assert_eq!(
+            *resources.get::<u32>().unwrap(),
+            1,
+            "panicked systems are skipped"
+        );
+        let panics = executor.drain_system_panics().collect::<Vec<_>>();
+        assert_eq!(panics.len(), 1);
+        assert_eq!(panics[0].message, "sequential panic");
+    }
 }
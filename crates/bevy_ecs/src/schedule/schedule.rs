@@ -1,21 +1,83 @@
+use super::Stage;
 use crate::{
     resource::Resources,
     system::{System, SystemId, ThreadLocalExecution},
 };
-use bevy_hecs::World;
+use bevy_hecs::{ArchetypeComponent, World};
 use bevy_utils::{HashMap, HashSet};
-use std::{borrow::Cow, fmt};
+use std::{any::TypeId, borrow::Cow, fmt};
+
+/// How the systems in a [SystemSet] are ordered relative to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SetOrdering {
+    /// The systems are free to run in parallel, like any other systems in the stage
+    Parallel,
+    /// Each system runs after the system listed before it, even if their data access doesn't conflict
+    Sequential,
+}
+
+impl Default for SetOrdering {
+    fn default() -> Self {
+        SetOrdering::Parallel
+    }
+}
+
+/// A labeled group of systems that are added to (and removed from) a [Schedule] together
+pub struct SystemSet {
+    pub label: Cow<'static, str>,
+    pub systems: Vec<Box<dyn System>>,
+    pub ordering: SetOrdering,
+}
+
+impl SystemSet {
+    pub fn new(label: impl Into<Cow<'static, str>>) -> Self {
+        SystemSet {
+            label: label.into(),
+            systems: Vec::new(),
+            ordering: SetOrdering::default(),
+        }
+    }
+
+    pub fn with_system(mut self, system: Box<dyn System>) -> Self {
+        self.systems.push(system);
+        self
+    }
+
+    pub fn with_ordering(mut self, ordering: SetOrdering) -> Self {
+        self.ordering = ordering;
+        self
+    }
+}
+
+/// Two systems in the same stage that both write the same component, as reported by [Schedule::validate]. The executor
+/// never runs such systems at the same time, so they can't benefit from parallelism.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccessConflict {
+    pub stage: Cow<'static, str>,
+    pub first_system: SystemId,
+    pub first_system_name: Cow<'static, str>,
+    pub second_system: SystemId,
+    pub second_system_name: Cow<'static, str>,
+    pub component: TypeId,
+    /// The component's type name. Only available in debug builds.
+    pub component_name: Option<&'static str>,
+}
 
 /// An ordered collection of stages, which each contain an ordered list of [System]s.
 /// Schedules are essentially the "execution plan" for an App's systems.
 /// They are run on a given [World] and [Resources] reference.
 #[derive(Default)]
 pub struct Schedule {
-    pub(crate) stages: HashMap<Cow<'static, str>, Vec<Box<dyn System>>>,
+    pub(crate) stages: HashMap<Cow<'static, str>, Stage>,
     pub(crate) stage_order: Vec<Cow<'static, str>>,
-    pub(crate) system_ids: HashSet<SystemId>,
+    /// The stage and system ids of each system set, by label
+    pub(crate) system_sets: HashMap<Cow<'static, str>, (Cow<'static, str>, Vec<SystemId>)>,
     generation: usize,
     last_initialize_generation: usize,
+    force_sequential: bool,
+    keep_trackers: bool,
+    log_stages: bool,
+    on_generation_change: Option<Box<dyn Fn(usize) + Send + Sync>>,
 }
 
 impl fmt::Debug for Schedule {
@@ -25,7 +87,7 @@ impl fmt::Debug for Schedule {
         let stages = self
             .stage_order
             .iter()
-            .map(|s| (s, self.stages[s].iter().map(|s| (s.name(), s.id()))));
+            .map(|s| (s, self.stages[s].systems.iter().map(|s| (s.name(), s.id()))));
 
         for (stage, syss) in stages {
             writeln!(f, "    Stage \"{}\"", stage)?;
@@ -45,11 +107,24 @@ impl Schedule {
         if self.stages.get(&stage).is_some() {
             panic!("Stage already exists: {}", stage);
         } else {
-            self.stages.insert(stage.clone(), Vec::new());
+            self.stages.insert(stage.clone(), Stage::new(stage.clone()));
             self.stage_order.push(stage);
         }
     }
 
+    /// Inserts a stage at `index` in the stage order. If `index` is past the end of the stage order, the stage is added
+    /// at the end.
+    pub fn add_stage_at(&mut self, index: usize, stage: impl Into<Cow<'static, str>>) {
+        let stage: Cow<str> = stage.into();
+        if self.stages.get(&stage).is_some() {
+            panic!("Stage already exists: {}", stage);
+        }
+
+        let index = index.min(self.stage_order.len());
+        self.stages.insert(stage.clone(), Stage::new(stage.clone()));
+        self.stage_order.insert(index, stage);
+    }
+
     pub fn add_stage_after(
         &mut self,
         target: impl Into<Cow<'static, str>>,
@@ -69,7 +144,7 @@ impl Schedule {
             .map(|(i, _)| i)
             .unwrap_or_else(|| panic!("Target stage does not exist: {}", target));
 
-        self.stages.insert(stage.clone(), Vec::new());
+        self.stages.insert(stage.clone(), Stage::new(stage.clone()));
         self.stage_order.insert(target_index + 1, stage);
     }
 
@@ -92,31 +167,55 @@ impl Schedule {
             .map(|(i, _)| i)
             .unwrap_or_else(|| panic!("Target stage does not exist: {}", target));
 
-        self.stages.insert(stage.clone(), Vec::new());
+        self.stages.insert(stage.clone(), Stage::new(stage.clone()));
         self.stage_order.insert(target_index, stage);
     }
 
+    /// Renames a stage, keeping its systems and its position in the stage order
+    pub fn rename_stage(
+        &mut self,
+        stage: impl Into<Cow<'static, str>>,
+        new_name: impl Into<Cow<'static, str>>,
+    ) {
+        let stage: Cow<str> = stage.into();
+        let new_name: Cow<str> = new_name.into();
+        if self.stages.get(&new_name).is_some() {
+            panic!("Stage already exists: {}", new_name);
+        }
+
+        let mut stage_data = self
+            .stages
+            .remove(&stage)
+            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage));
+        let stage_index = self
+            .stage_order
+            .iter()
+            .position(|stage_name| *stage_name == stage)
+            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage));
+
+        stage_data.set_name(new_name.clone());
+        self.stages.insert(new_name.clone(), stage_data);
+        for (set_stage_name, _) in self.system_sets.values_mut() {
+            if *set_stage_name == stage {
+                *set_stage_name = new_name.clone();
+            }
+        }
+        self.stage_order[stage_index] = new_name;
+        self.increment_generation();
+    }
+
     pub fn add_system_to_stage(
         &mut self,
         stage_name: impl Into<Cow<'static, str>>,
         system: Box<dyn System>,
     ) -> &mut Self {
         let stage_name = stage_name.into();
-        let systems = self
-            .stages
+        self.stages
             .get_mut(&stage_name)
-            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage_name));
-        if self.system_ids.contains(&system.id()) {
-            panic!(
-                "System with id {:?} ({}) already exists",
-                system.id(),
-                system.name()
-            );
-        }
-        self.system_ids.insert(system.id());
-        systems.push(system);
+            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage_name))
+            .add_system(system);
 
-        self.generation += 1;
+        self.increment_generation();
         self
     }
 
@@ -126,68 +225,150 @@ impl Schedule {
         system: Box<dyn System>,
     ) -> &mut Self {
         let stage_name = stage_name.into();
-        let systems = self
-            .stages
+        self.stages
             .get_mut(&stage_name)
-            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage_name));
-        if self.system_ids.contains(&system.id()) {
-            panic!(
-                "System with id {:?} ({}) already exists",
-                system.id(),
-                system.name()
-            );
+            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage_name))
+            .add_system_front(system);
+
+        self.increment_generation();
+        self
+    }
+
+    /// Adds every system in `system_set` to the end of the given stage. The systems can later be removed together using
+    /// the set's label. If the set is [SetOrdering::Sequential], each system runs after the one listed before it.
+    pub fn add_system_set_to_stage(
+        &mut self,
+        stage_name: impl Into<Cow<'static, str>>,
+        system_set: SystemSet,
+    ) -> &mut Self {
+        let stage_name = stage_name.into();
+        if self.system_sets.contains_key(&system_set.label) {
+            panic!("System set already exists: {}", system_set.label);
         }
-        self.system_ids.insert(system.id());
-        systems.insert(0, system);
 
-        self.generation += 1;
+        let mut system_ids = Vec::with_capacity(system_set.systems.len());
+        for system in system_set.systems {
+            system_ids.push(system.id());
+            self.add_system_to_stage(stage_name.clone(), system);
+        }
+        if system_set.ordering == SetOrdering::Sequential {
+            let stage = self.stages.get_mut(&stage_name).unwrap();
+            for pair in system_ids.windows(2) {
+                stage.set_run_after(pair[1], pair[0]);
+            }
+        }
+        self.system_sets
+            .insert(system_set.label, (stage_name, system_ids));
         self
     }
 
+    /// Returns the ids of the systems in the system set with the given label
+    pub fn system_set(&self, label: &str) -> Option<&[SystemId]> {
+        self.system_sets
+            .get(label)
+            .map(|(_, system_ids)| system_ids.as_slice())
+    }
+
+    /// Removes the system with the given id and returns it. Systems in different stages may share an id; in that case
+    /// only the system in the stage that runs first is removed. Use [Schedule::remove_system_from_stage] to pick the
+    /// stage.
+    pub fn remove_system(&mut self, system_id: SystemId) -> Option<Box<dyn System>> {
+        let stages = &self.stages;
+        let stage_name = self
+            .stage_order
+            .iter()
+            .find(|stage_name| {
+                stages.get(*stage_name).map_or(false, |stage| {
+                    stage.systems.iter().any(|system| system.id() == system_id)
+                })
+            })?
+            .clone();
+        self.remove_system_from_stage(&stage_name, system_id)
+    }
+
+    /// Removes the system with the given id from the given stage and returns it. Returns `None` if the stage does not
+    /// exist or doesn't contain the system.
+    pub fn remove_system_from_stage(
+        &mut self,
+        stage_name: &str,
+        system_id: SystemId,
+    ) -> Option<Box<dyn System>> {
+        let system = self.stages.get_mut(stage_name)?.remove_system(system_id)?;
+
+        for (set_stage_name, system_ids) in self.system_sets.values_mut() {
+            if set_stage_name == stage_name {
+                system_ids.retain(|id| *id != system_id);
+            }
+        }
+
+        self.increment_generation();
+        Some(system)
+    }
+
+    /// Removes every system in the system set with the given label from the set's stage and returns them
+    pub fn remove_system_set(&mut self, label: &str) -> Vec<Box<dyn System>> {
+        let (stage_name, system_ids) = match self.system_sets.remove(label) {
+            Some(system_set) => system_set,
+            None => return Vec::new(),
+        };
+        system_ids
+            .into_iter()
+            .filter_map(|system_id| self.remove_system_from_stage(&stage_name, system_id))
+            .collect()
+    }
+
     pub fn run(&mut self, world: &mut World, resources: &mut Resources) {
         for stage_name in self.stage_order.iter() {
-            if let Some(stage_systems) = self.stages.get_mut(stage_name) {
-                for system in stage_systems.iter_mut() {
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_start(resources, system.name().clone());
-                    system.update(world);
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => system.run(world, resources),
-                        ThreadLocalExecution::Immediate => {
-                            system.run(world, resources);
-                            // NOTE: when this is made parallel a full sync is required here
-                            system.run_thread_local(world, resources);
-                        }
-                    }
-                    #[cfg(feature = "profiler")]
-                    crate::profiler_stop(resources, system.name().clone());
+            if let Some(stage) = self.stages.get_mut(stage_name) {
+                if self.log_stages {
+                    log::debug!("stage {} started ({} systems)", stage_name, stage.len());
                 }
-
-                // "flush"
-                // NOTE: when this is made parallel a full sync is required here
-                for system in stage_systems.iter_mut() {
-                    match system.thread_local_execution() {
-                        ThreadLocalExecution::NextFlush => {
-                            system.run_thread_local(world, resources)
-                        }
-                        ThreadLocalExecution::Immediate => { /* already ran immediate */ }
-                    }
+                stage.run(world, resources);
+                if self.log_stages {
+                    log::debug!("stage {} finished", stage_name);
                 }
             }
         }
 
-        world.clear_trackers();
-        resources.clear_trackers();
+        if !self.keep_trackers {
+            world.clear_trackers();
+            resources.clear_trackers();
+        }
+    }
+
+    /// Runs the systems in the given stage (including their thread local flush) without running any other stage.
+    /// Unlike [Schedule::run], this does not clear the world and resource trackers.
+    pub fn run_stage_once(
+        &mut self,
+        stage_name: impl Into<Cow<'static, str>>,
+        world: &mut World,
+        resources: &mut Resources,
+    ) {
+        let stage_name = stage_name.into();
+        self.stages
+            .get_mut(&stage_name)
+            .unwrap_or_else(|| panic!("Stage does not exist: {}", stage_name))
+            .run(world, resources);
     }
 
     // TODO: move this code to ParallelExecutor
     pub fn initialize(&mut self, world: &mut World, resources: &mut Resources) {
-        if self.last_initialize_generation == self.generation {
+        if !self.needs_initialize() {
             return;
         }
 
+        // a stage missing from the stage order never runs, so its systems would silently do nothing
+        for stage_name in self.stages.keys() {
+            if !self.stage_order.contains(stage_name) {
+                panic!("Stage is not in the stage order: {}", stage_name);
+            }
+        }
+        if let Some(stage_name) = self.missing_stages().first() {
+            panic!("Stage does not exist: {}", stage_name);
+        }
+
         for stage in self.stages.values_mut() {
-            for system in stage.iter_mut() {
+            for system in stage.systems.iter_mut() {
                 system.initialize(world, resources);
             }
         }
@@ -195,17 +376,221 @@ impl Schedule {
         self.last_initialize_generation = self.generation;
     }
 
+    /// Reports every pair of systems in the same stage that could otherwise run in parallel but both write the same
+    /// component in the given [World]. Systems separated by a [ThreadLocalExecution::Immediate] system never run at the
+    /// same time, so they are not reported. Each system's access is updated to the world's current archetypes first.
+    pub fn validate(&mut self, world: &World) -> Vec<AccessConflict> {
+        let mut conflicts = Vec::new();
+        for stage_name in self.stage_order.iter() {
+            let stage_systems = match self.stages.get_mut(stage_name) {
+                Some(stage) => &mut stage.systems,
+                None => continue,
+            };
+
+            for system in stage_systems.iter_mut() {
+                system.update(world);
+            }
+
+            let mut batch_start = 0;
+            for (system_index, system) in stage_systems.iter().enumerate() {
+                if system.thread_local_execution() == ThreadLocalExecution::Immediate {
+                    batch_start = system_index + 1;
+                    continue;
+                }
+
+                let archetype_access = system.archetype_component_access();
+                for earlier_system in stage_systems[batch_start..system_index].iter() {
+                    let mut components = HashSet::default();
+                    for archetype_component in
+                        earlier_system.archetype_component_access().iter_writes()
+                    {
+                        if archetype_access.is_write(archetype_component)
+                            && components.insert(archetype_component.component)
+                        {
+                            conflicts.push(AccessConflict {
+                                stage: stage_name.clone(),
+                                first_system: earlier_system.id(),
+                                first_system_name: earlier_system.name(),
+                                second_system: system.id(),
+                                second_system_name: system.name(),
+                                component: archetype_component.component,
+                                component_name: component_name(world, archetype_component),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Returns the stage with the given name. Stages can only be changed through the [Schedule], which lets executors
+    /// know when to rebuild their system dependencies.
+    pub fn stage(&self, stage_name: &str) -> Option<&Stage> {
+        self.stages.get(stage_name)
+    }
+
+    /// Returns the names of the stages in this schedule, in the order they run
+    pub fn stage_names(&self) -> Vec<&str> {
+        self.stage_order
+            .iter()
+            .map(|stage| stage.as_ref())
+            .collect()
+    }
+
+    /// Describes when each system's thread local logic (for example, its [Commands](crate::Commands)) is applied, stage by
+    /// stage. [ThreadLocalExecution::NextFlush] systems are applied at the end of their stage, so their changes are
+    /// only visible to later stages. [ThreadLocalExecution::Immediate] systems are applied as soon as they finish, so
+    /// their changes are visible to every system registered after them in the same stage.
+    pub fn thread_local_execution_report(&self) -> String {
+        let mut report = String::new();
+        for stage_name in self.stage_order.iter() {
+            report.push_str(&format!("Stage \"{}\"\n", stage_name));
+            if let Some(stage) = self.stages.get(stage_name) {
+                for system in stage.systems.iter() {
+                    let applied = match system.thread_local_execution() {
+                        ThreadLocalExecution::NextFlush => "applied at the end of the stage",
+                        ThreadLocalExecution::Immediate => "applied before the next system runs",
+                    };
+                    report.push_str(&format!(
+                        "    {} ({:?}): {:?}, {}\n",
+                        system.name(),
+                        system.id(),
+                        system.thread_local_execution(),
+                        applied
+                    ));
+                }
+            }
+        }
+        report
+    }
+
+    /// Returns the names of the stages that contain no systems, in the order they run
+    pub fn empty_stages(&self) -> Vec<&str> {
+        self.stage_order
+            .iter()
+            .filter(|stage_name| {
+                self.stages
+                    .get(*stage_name)
+                    .map_or(false, |stage| stage.is_empty())
+            })
+            .map(|stage_name| stage_name.as_ref())
+            .collect()
+    }
+
+    /// Returns the names in the stage order that don't have a stage. Systems can't be added to these, so this indicates
+    /// a bug in how the schedule was built.
+    pub fn missing_stages(&self) -> Vec<&str> {
+        self.stage_order
+            .iter()
+            .filter(|stage_name| !self.stages.contains_key(*stage_name))
+            .map(|stage_name| stage_name.as_ref())
+            .collect()
+    }
+
+    /// Returns the id and name of each system in the given stage, in the order they were added. Returns `None` if the
+    /// stage does not exist.
+    pub fn systems_in_stage(&self, stage_name: &str) -> Option<Vec<(SystemId, Cow<'static, str>)>> {
+        self.stages.get(stage_name).map(|stage| {
+            stage
+                .systems
+                .iter()
+                .map(|system| (system.id(), system.name()))
+                .collect()
+        })
+    }
+
+    /// Returns the number of systems in every stage of this schedule
+    pub fn system_count(&self) -> usize {
+        self.stages.values().map(|stage| stage.len()).sum()
+    }
+
+    /// Returns the number of systems in the given stage, or `None` if the stage does not exist
+    pub fn systems_in_stage_count(&self, stage_name: &str) -> Option<usize> {
+        self.stages.get(stage_name).map(|stage| stage.len())
+    }
+
+    /// Returns the ids of every system whose name is `name`, in stage order. System names aren't unique, so this may
+    /// return more than one id.
+    pub fn find_systems_by_name(&self, name: &str) -> Vec<SystemId> {
+        self.stage_order
+            .iter()
+            .filter_map(|stage_name| self.stages.get(stage_name))
+            .flat_map(|stage| stage.systems.iter())
+            .filter(|system| system.name() == name)
+            .map(|system| system.id())
+            .collect()
+    }
+
+    /// When set, executors run this schedule's systems one at a time on the calling thread, strictly in the order they
+    /// were added to their stage. This makes runs reproducible (for example, for golden-file tests) at the cost of
+    /// parallelism.
+    pub fn set_force_sequential(&mut self, force_sequential: bool) {
+        self.force_sequential = force_sequential;
+    }
+
+    pub fn force_sequential(&self) -> bool {
+        self.force_sequential
+    }
+
+    /// Controls whether the world and resource change trackers are cleared after this schedule runs (the default). Turn
+    /// this off to clear them somewhere else, for example from a dedicated system.
+    pub fn set_clear_trackers_after_run(&mut self, clear_trackers_after_run: bool) {
+        self.keep_trackers = !clear_trackers_after_run;
+    }
+
+    pub fn clear_trackers_after_run(&self) -> bool {
+        !self.keep_trackers
+    }
+
+    /// When set, executors log a debug message as each stage of this schedule starts (with its system count) and finishes
+    pub fn set_log_stages(&mut self, log_stages: bool) {
+        self.log_stages = log_stages;
+    }
+
+    pub fn log_stages(&self) -> bool {
+        self.log_stages
+    }
+
     pub fn generation(&self) -> usize {
         self.generation
     }
 
+    /// Returns true if systems were added to or removed from this schedule since it was last initialized
+    pub fn needs_initialize(&self) -> bool {
+        self.last_initialize_generation != self.generation
+    }
+
+    /// Sets a callback that is invoked with the new generation every time this schedule's systems change
+    pub fn set_on_generation_change(&mut self, callback: impl Fn(usize) + Send + Sync + 'static) {
+        self.on_generation_change = Some(Box::new(callback));
+    }
+
+    fn increment_generation(&mut self) {
+        self.generation += 1;
+        if let Some(on_generation_change) = self.on_generation_change.as_ref() {
+            on_generation_change(self.generation);
+        }
+    }
+
     pub fn run_on_systems(&mut self, mut func: impl FnMut(&mut dyn System)) {
         for stage_name in self.stage_order.iter() {
-            if let Some(stage_systems) = self.stages.get_mut(stage_name) {
-                for system in stage_systems.iter_mut() {
+            if let Some(stage) = self.stages.get_mut(stage_name) {
+                for system in stage.systems.iter_mut() {
                     func(&mut **system);
                 }
             }
         }
     }
 }
+
+fn component_name(world: &World, archetype_component: &ArchetypeComponent) -> Option<&'static str> {
+    world
+        .archetypes()
+        .nth(archetype_component.archetype_index as usize)?
+        .types()
+        .iter()
+        .find(|type_info| type_info.id() == archetype_component.component)?
+        .type_name()
+}
@@ -0,0 +1,151 @@
+use crate::{
+    resource::Resources,
+    system::{Profiler, System, SystemId, ThreadLocalExecution},
+};
+use bevy_hecs::World;
+use bevy_utils::HashMap;
+use std::borrow::Cow;
+
+/// A named, ordered list of [System]s in a [Schedule](super::Schedule). All systems in a stage finish (and have their
+/// thread local logic flushed) before the next stage starts.
+pub struct Stage {
+    name: Cow<'static, str>,
+    pub(crate) systems: Vec<Box<dyn System>>,
+    /// Maps a system to the system it has to run after. Filled in by sequential [SystemSet](super::SystemSet)s.
+    run_after: HashMap<SystemId, SystemId>,
+}
+
+impl Stage {
+    pub(crate) fn new(name: Cow<'static, str>) -> Self {
+        Stage {
+            name,
+            systems: Vec::new(),
+            run_after: HashMap::default(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub(crate) fn set_name(&mut self, name: Cow<'static, str>) {
+        self.name = name;
+    }
+
+    /// Returns this stage's systems in the order they were added
+    pub fn systems(&self) -> &[Box<dyn System>] {
+        &self.systems
+    }
+
+    pub fn len(&self) -> usize {
+        self.systems.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+
+    pub(crate) fn add_system(&mut self, system: Box<dyn System>) {
+        self.check_duplicate_system(&*system);
+        self.systems.push(system);
+    }
+
+    pub(crate) fn add_system_front(&mut self, system: Box<dyn System>) {
+        self.check_duplicate_system(&*system);
+        self.systems.insert(0, system);
+    }
+
+    pub(crate) fn remove_system(&mut self, system_id: SystemId) -> Option<Box<dyn System>> {
+        let index = self
+            .systems
+            .iter()
+            .position(|system| system.id() == system_id)?;
+
+        // keep the rest of an ordered chain in order by linking the removed system's successor to its predecessor
+        let predecessor = self.run_after.remove(&system_id);
+        let successor = self
+            .run_after
+            .iter()
+            .find(|(_, after)| **after == system_id)
+            .map(|(id, _)| *id);
+        if let Some(successor) = successor {
+            match predecessor {
+                Some(predecessor) => self.run_after.insert(successor, predecessor),
+                None => self.run_after.remove(&successor),
+            };
+        }
+
+        Some(self.systems.remove(index))
+    }
+
+    pub(crate) fn set_run_after(&mut self, system_id: SystemId, after: SystemId) {
+        self.run_after.insert(system_id, after);
+    }
+
+    /// Returns the index of the system each system has to run after, if any
+    pub(crate) fn run_after_indices(&self) -> Vec<Option<usize>> {
+        let indices = self
+            .systems
+            .iter()
+            .enumerate()
+            .map(|(index, system)| (system.id(), index))
+            .collect::<HashMap<_, _>>();
+        self.systems
+            .iter()
+            .map(|system| {
+                self.run_after
+                    .get(&system.id())
+                    .and_then(|after| indices.get(after).copied())
+            })
+            .collect()
+    }
+
+    /// Systems are identified by their [SystemId] within a stage, so the same id can't be added to a stage twice.
+    /// Different stages may contain systems with the same id.
+    fn check_duplicate_system(&self, system: &dyn System) {
+        if self
+            .systems
+            .iter()
+            .any(|existing| existing.id() == system.id())
+        {
+            panic!(
+                "System with id {:?} ({}) already exists in stage {}",
+                system.id(),
+                system.name(),
+                self.name
+            );
+        }
+    }
+
+    /// Runs each system in this stage one at a time on the calling thread, in the order they were added, followed by the
+    /// thread local flush
+    pub fn run(&mut self, world: &mut World, resources: &mut Resources) {
+        let profile = resources.contains::<Box<dyn Profiler>>();
+        for system in self.systems.iter_mut() {
+            if profile {
+                crate::profiler_start(resources, system.name());
+            }
+            system.update(world);
+            match system.thread_local_execution() {
+                ThreadLocalExecution::NextFlush => system.run(world, resources),
+                ThreadLocalExecution::Immediate => {
+                    system.run(world, resources);
+                    // NOTE: when this is made parallel a full sync is required here
+                    system.run_thread_local(world, resources);
+                }
+            }
+            if profile {
+                crate::profiler_stop(resources, system.name());
+            }
+        }
+
+        // "flush"
+        // NOTE: when this is made parallel a full sync is required here
+        for system in self.systems.iter_mut() {
+            match system.thread_local_execution() {
+                ThreadLocalExecution::NextFlush => system.run_thread_local(world, resources),
+                ThreadLocalExecution::Immediate => { /* already ran immediate */ }
+            }
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::system::SystemId;
+use bevy_utils::HashMap;
+use std::{borrow::Cow, time::Duration};
+
+/// How long a system took the last time it ran
+#[derive(Debug, Clone)]
+pub struct SystemTiming {
+    pub name: Cow<'static, str>,
+    pub duration: Duration,
+}
+
+/// Records the duration of each system's most recent run.
+///
+/// Insert this resource and set `enabled` to start collecting timings. The [ParallelExecutor](crate::ParallelExecutor)
+/// checks `enabled` once per schedule run, so leaving the resource in place while disabled costs a single branch per system.
+#[derive(Debug, Default)]
+pub struct SystemStats {
+    pub enabled: bool,
+    timings: HashMap<SystemId, SystemTiming>,
+}
+
+impl SystemStats {
+    pub fn enabled() -> Self {
+        SystemStats {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, id: SystemId) -> Option<&SystemTiming> {
+        self.timings.get(&id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (SystemId, &SystemTiming)> {
+        self.timings.iter().map(|(id, timing)| (*id, timing))
+    }
+
+    /// Returns up to `count` systems ordered from the longest to the shortest last run duration
+    pub fn slowest(&self, count: usize) -> Vec<(SystemId, &SystemTiming)> {
+        let mut timings = self.iter().collect::<Vec<_>>();
+        timings.sort_by(|(_, a), (_, b)| b.duration.cmp(&a.duration));
+        timings.truncate(count);
+        timings
+    }
+
+    pub fn record(&mut self, id: SystemId, name: Cow<'static, str>, duration: Duration) {
+        self.timings.insert(id, SystemTiming { name, duration });
+    }
+
+    pub fn clear(&mut self) {
+        self.timings.clear();
+    }
+}
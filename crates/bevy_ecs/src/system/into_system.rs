@@ -206,7 +206,7 @@ mod tests {
     use crate::{
         resource::{ResMut, Resources},
         schedule::Schedule,
-        ChangedRes, Query, QuerySet, System,
+        ChangedRes, ExistingRes, Query, QuerySet, System,
     };
     use bevy_hecs::{Entity, Or, With, World};
 
@@ -335,6 +335,31 @@ mod tests {
         assert_eq!(*(world.get::<i32>(ent).unwrap()), 2);
     }
 
+    #[test]
+    fn existing_resource_system() {
+        fn incr_e_if_exists(_loaded: ExistingRes<bool>, mut query: Query<&mut i32>) {
+            for mut i in query.iter_mut() {
+                *i += 1;
+            }
+        }
+
+        let mut world = World::default();
+        let mut resources = Resources::default();
+        let ent = world.spawn((0,));
+
+        let mut schedule = Schedule::default();
+        schedule.add_stage("update");
+        schedule.add_system_to_stage("update", incr_e_if_exists.system());
+        schedule.initialize(&mut world, &mut resources);
+
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*(world.get::<i32>(ent).unwrap()), 0);
+
+        resources.insert(true);
+        schedule.run(&mut world, &mut resources);
+        assert_eq!(*(world.get::<i32>(ent).unwrap()), 1);
+    }
+
     #[test]
     fn changed_resource_or_system() {
         fn incr_e_on_flip(
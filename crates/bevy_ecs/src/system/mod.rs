@@ -1,7 +1,6 @@
 mod commands;
 mod into_system;
 mod into_thread_local;
-#[cfg(feature = "profiler")]
 mod profiler;
 mod query;
 #[allow(clippy::module_inception)]
@@ -11,7 +10,6 @@ mod system_param;
 pub use commands::*;
 pub use into_system::*;
 pub use into_thread_local::*;
-#[cfg(feature = "profiler")]
 pub use profiler::*;
 pub use query::*;
 pub use system::*;
@@ -4,7 +4,9 @@ use std::borrow::Cow;
 
 /// Runs at the start and end of each system
 ///
-/// Profilers are used to collect diagnostics about system execution.
+/// Profilers are used to collect diagnostics about system execution. To use one, insert it as a `Box<dyn Profiler>`
+/// resource. Executors check for the resource once per run, so when none exists systems run without profiling
+/// overhead.
 pub trait Profiler: Downcast + Send + Sync + 'static {
     fn start(&self, scope: Cow<'static, str>);
     fn stop(&self, scope: Cow<'static, str>);
@@ -1,6 +1,6 @@
 use crate::{
-    ChangedRes, Commands, FromResources, Local, Query, QueryAccess, QuerySet, QueryTuple, Res,
-    ResMut, Resource, ResourceIndex, Resources, SystemState,
+    ChangedRes, Commands, ExistingRes, FromResources, Local, Query, QueryAccess, QuerySet,
+    QueryTuple, Res, ResMut, Resource, ResourceIndex, Resources, SystemState,
 };
 pub use bevy_hecs::SystemParam;
 use bevy_hecs::{
@@ -167,6 +167,27 @@ impl<'a, T: Resource> SystemParam for ChangedRes<'a, T> {
     }
 }
 
+impl<'a, T: Resource> SystemParam for ExistingRes<'a, T> {
+    fn init(system_state: &mut SystemState, _world: &World, _resources: &mut Resources) {
+        system_state.resource_access.add_read(TypeId::of::<T>());
+    }
+
+    #[inline]
+    unsafe fn get_param(
+        _system_state: &mut SystemState,
+        _world: &World,
+        resources: &Resources,
+    ) -> Option<Self> {
+        if resources.contains_index::<T>(ResourceIndex::Global) {
+            Some(ExistingRes::new(
+                resources.get_unsafe_ref::<T>(ResourceIndex::Global),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
 impl<'a, T: Resource + FromResources> SystemParam for Local<'a, T> {
     fn init(system_state: &mut SystemState, _world: &World, resources: &mut Resources) {
         system_state.resource_access.add_write(TypeId::of::<T>());
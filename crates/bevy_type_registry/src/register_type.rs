@@ -19,8 +19,40 @@ pub trait RegisterType {
     fn register_property<T>(&mut self) -> &mut Self
     where
         T: Property + DeserializeProperty;
+    /// Registers every component in the tuple `T`, as if [RegisterType::register_component] was called for each one
+    fn register_components<T: ComponentTuple>(&mut self) -> &mut Self;
 }
 
+/// A tuple of components that can be registered at once with [RegisterType::register_components]
+pub trait ComponentTuple {
+    fn register(app: &mut AppBuilder);
+}
+
+macro_rules! impl_component_tuple {
+    ($($component: ident),*) => {
+        impl<$($component: Properties + DeserializeProperty + Component + FromResources),*> ComponentTuple for ($($component,)*) {
+            #[allow(unused_variables)]
+            fn register(app: &mut AppBuilder) {
+                $(app.register_component::<$component>();)*
+            }
+        }
+    };
+}
+
+impl_component_tuple!();
+impl_component_tuple!(A);
+impl_component_tuple!(A, B);
+impl_component_tuple!(A, B, C);
+impl_component_tuple!(A, B, C, D);
+impl_component_tuple!(A, B, C, D, E);
+impl_component_tuple!(A, B, C, D, E, F);
+impl_component_tuple!(A, B, C, D, E, F, G);
+impl_component_tuple!(A, B, C, D, E, F, G, H);
+impl_component_tuple!(A, B, C, D, E, F, G, H, I);
+impl_component_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_component_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_component_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+
 impl RegisterType for AppBuilder {
     fn register_component<T>(&mut self) -> &mut Self
     where
@@ -56,6 +88,11 @@ impl RegisterType for AppBuilder {
         self
     }
 
+    fn register_components<T: ComponentTuple>(&mut self) -> &mut Self {
+        T::register(self);
+        self
+    }
+
     fn register_component_with<T>(
         &mut self,
         build: fn(ComponentRegistrationBuilder<T>) -> ComponentRegistrationBuilder<T>,
@@ -21,6 +21,6 @@ pub fn exit_on_window_close_system(
         .next()
         .is_some()
     {
-        app_exit_events.send(AppExit);
+        app_exit_events.send(AppExit::success());
     }
 }
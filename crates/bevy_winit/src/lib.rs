@@ -166,6 +166,7 @@ pub fn winit_runner(mut app: App) {
         .resources
         .get::<WinitConfig>()
         .map_or(false, |config| config.return_from_run);
+    let mut app_exit = None;
 
     let event_handler = move |event: Event<()>,
                               event_loop: &EventLoopWindowTarget<()>,
@@ -177,7 +178,8 @@ pub fn winit_runner(mut app: App) {
         };
 
         if let Some(app_exit_events) = app.resources.get_mut::<Events<AppExit>>() {
-            if app_exit_event_reader.latest(&app_exit_events).is_some() {
+            if let Some(exit) = app_exit_event_reader.latest(&app_exit_events) {
+                app_exit = Some(*exit);
                 *control_flow = ControlFlow::Exit;
             }
         }
@@ -306,6 +308,14 @@ pub fn winit_runner(mut app: App) {
                 );
                 app.update();
             }
+            event::Event::LoopDestroyed => {
+                app.shutdown();
+                if let Some(app_exit) = app_exit {
+                    if !should_return_from_run {
+                        app_exit.exit_process_on_failure();
+                    }
+                }
+            }
             _ => (),
         }
     };
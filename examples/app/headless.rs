@@ -1,5 +1,4 @@
 use bevy::{app::ScheduleRunnerSettings, prelude::*};
-use std::time::Duration;
 
 // This example only enables a minimal set of plugins required for bevy to run.
 // You can also completely remove rendering / windowing Plugin code from bevy
@@ -19,9 +18,7 @@ fn main() {
 
     // this app loops forever at 60 fps
     App::build()
-        .add_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
-            1.0 / 60.0,
-        )))
+        .add_resource(ScheduleRunnerSettings::run_at_fps(60.0))
         .add_plugins(MinimalPlugins)
         .add_system(counter.system())
         .run();
@@ -5,7 +5,6 @@ use bevy::{
     app::{ScheduleRunnerPlugin, ScheduleRunnerSettings},
     prelude::*,
 };
-use std::time::Duration;
 
 fn main() {
     #[cfg(target_arch = "wasm32")]
@@ -15,9 +14,7 @@ fn main() {
     }
 
     App::build()
-        .add_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
-            1.0 / 60.0,
-        )))
+        .add_resource(ScheduleRunnerSettings::run_at_fps(60.0))
         .add_plugin(ScheduleRunnerPlugin::default())
         .add_startup_system(hello_world_system.system())
         .add_system(counter.system())